@@ -1,16 +1,8 @@
-use lipl_api_client::{ApiClientBuilder, Authentication, LiplApiClient};
+use lipl_api_client::{ApiClientBuilder, LiplApiClient};
 use lipl_core::{HasSummary, LiplRepo, Result, error::reqwest_error};
 
 const PREFIX: &str = "https://www.paulmin.nl/lipl/api/v1/";
 
-fn username() -> String {
-    std::env::var("LIPL_USERNAME").unwrap()
-}
-
-fn password() -> String {
-    std::env::var("LIPL_PASSWORD").unwrap()
-}
-
 trait VecExt {
     fn display_titles(self, name: &str, seperator: &str) -> String;
 }
@@ -32,9 +24,8 @@ where
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
-    let auth = Authentication::new_basic(&username(), &password());
     let client = ApiClientBuilder::new(PREFIX)
-        .authentication(auth)
+        .discover_authentication("LIPL")
         .build()
         .map_err(reqwest_error)
         .map(LiplApiClient::from)?;
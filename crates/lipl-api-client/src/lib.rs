@@ -1,14 +1,35 @@
 use futures_util::future::BoxFuture;
-use futures_util::{FutureExt, TryFutureExt};
+use futures_util::{FutureExt, TryFutureExt, TryStreamExt};
 use lipl_core::{
     LiplRepo, Lyric, LyricPost, Playlist, PlaylistPost, Result, Summary, Uuid, error::reqwest_error,
 };
+use rest_json_client::Method;
 pub use rest_json_client::{ApiClient, ApiClientBuilder};
-pub use rest_json_client::{Authentication, BasicAuthentication};
+pub use rest_json_client::{ApiKeyLocation, Authentication, BasicAuthentication};
+use std::future::Future;
 
 const LYRIC: &str = "lyric";
 const PLAYLIST: &str = "playlist";
-const FULL: &str = "full=true";
+const FULL: [(&str, bool); 1] = [("full", true)];
+const LYRIC_ID: &str = "lyric/{id}";
+const PLAYLIST_ID: &str = "playlist/{id}";
+
+/// Run `future` in a span named after the repository operation, so that the requests it makes are nested in it
+#[cfg(feature = "tracing")]
+fn traced<'a, T>(
+    operation: &'static str,
+    future: impl Future<Output = T> + Send + 'a,
+) -> BoxFuture<'a, T> {
+    use tracing::Instrument;
+    future
+        .instrument(tracing::info_span!("lipl", operation))
+        .boxed()
+}
+
+#[cfg(not(feature = "tracing"))]
+fn traced<'a, T>(_: &'static str, future: impl Future<Output = T> + Send + 'a) -> BoxFuture<'a, T> {
+    future.boxed()
+}
 
 pub struct LiplApiClient {
     api_client: ApiClient,
@@ -32,94 +53,107 @@ impl LiplApiClient {
 
 impl LiplRepo for LiplApiClient {
     fn get_lyrics(&self) -> BoxFuture<'_, Result<Vec<Lyric>>> {
-        async move {
+        traced("get_lyrics", async move {
             self.api_client
-                .get(&format!("{LYRIC}?{FULL}"))
+                .request(Method::GET, LYRIC)
+                .query(&FULL)
+                .send_stream()
+                .try_collect()
                 .map_err(reqwest_error)
                 .await
-        }
-        .boxed()
+        })
     }
 
     fn get_lyric_summaries(&self) -> BoxFuture<'_, Result<Vec<Summary>>> {
-        async move { self.api_client.get(LYRIC).map_err(reqwest_error).await }.boxed()
+        traced("get_lyric_summaries", async move {
+            self.api_client.get(LYRIC).map_err(reqwest_error).await
+        })
     }
 
     fn get_lyric(&self, uuid: Uuid) -> BoxFuture<'_, Result<Lyric>> {
-        async move {
+        traced("get_lyric", async move {
             self.api_client
-                .get(&format!("{LYRIC}/{uuid}"))
+                .request(Method::GET, &format!("{LYRIC}/{uuid}"))
+                .route(LYRIC_ID)
+                .send_json()
                 .map_err(reqwest_error)
                 .await
-        }
-        .boxed()
+        })
     }
 
     fn upsert_lyric(&self, lyric: Lyric) -> BoxFuture<'_, Result<Lyric>> {
-        async move {
+        traced("upsert_lyric", async move {
             self.api_client
-                .post(&format!("{LYRIC}/{}", lyric.id), LyricPost::from(lyric))
+                .request(Method::POST, &format!("{LYRIC}/{}", lyric.id))
+                .route(LYRIC_ID)
+                .json(&LyricPost::from(lyric))
+                .send_json()
                 .map_err(reqwest_error)
                 .await
-        }
-        .boxed()
+        })
     }
 
     fn delete_lyric(&self, uuid: Uuid) -> BoxFuture<'_, Result<()>> {
-        async move {
+        traced("delete_lyric", async move {
             self.api_client
-                .delete(&format!("{LYRIC}/{uuid}"))
+                .request(Method::DELETE, &format!("{LYRIC}/{uuid}"))
+                .route(LYRIC_ID)
+                .send_empty()
                 .map_err(reqwest_error)
                 .await
-        }
-        .boxed()
+        })
     }
 
     fn get_playlists(&self) -> BoxFuture<'_, Result<Vec<Playlist>>> {
-        async move {
+        traced("get_playlists", async move {
             self.api_client
-                .get(&format!("{PLAYLIST}?{FULL}"))
+                .request(Method::GET, PLAYLIST)
+                .query(&FULL)
+                .send_stream()
+                .try_collect()
                 .map_err(reqwest_error)
                 .await
-        }
-        .boxed()
+        })
     }
 
     fn get_playlist_summaries(&self) -> BoxFuture<'_, Result<Vec<Summary>>> {
-        async move { self.api_client.get(PLAYLIST).map_err(reqwest_error).await }.boxed()
+        traced("get_playlist_summaries", async move {
+            self.api_client.get(PLAYLIST).map_err(reqwest_error).await
+        })
     }
 
     fn get_playlist(&self, uuid: Uuid) -> BoxFuture<'_, Result<Playlist>> {
-        async move {
+        traced("get_playlist", async move {
             self.api_client
-                .get(&format!("{PLAYLIST}/{uuid}"))
+                .request(Method::GET, &format!("{PLAYLIST}/{uuid}"))
+                .route(PLAYLIST_ID)
+                .send_json()
                 .map_err(reqwest_error)
                 .await
-        }
-        .boxed()
+        })
     }
 
     fn upsert_playlist(&self, playlist: Playlist) -> BoxFuture<'_, Result<Playlist>> {
-        async move {
+        traced("upsert_playlist", async move {
             self.api_client
-                .post(
-                    &format!("{PLAYLIST}/{}", playlist.id),
-                    PlaylistPost::from(playlist),
-                )
+                .request(Method::POST, &format!("{PLAYLIST}/{}", playlist.id))
+                .route(PLAYLIST_ID)
+                .json(&PlaylistPost::from(playlist))
+                .send_json()
                 .map_err(reqwest_error)
                 .await
-        }
-        .boxed()
+        })
     }
 
     fn delete_playlist(&self, uuid: Uuid) -> BoxFuture<'_, Result<()>> {
-        async move {
+        traced("delete_playlist", async move {
             self.api_client
-                .delete(&format!("{PLAYLIST}/{uuid}"))
+                .request(Method::DELETE, &format!("{PLAYLIST}/{uuid}"))
+                .route(PLAYLIST_ID)
+                .send_empty()
                 .map_err(reqwest_error)
                 .await
-        }
-        .boxed()
+        })
     }
 
     fn stop(&self) -> BoxFuture<'_, Result<()>> {
@@ -0,0 +1,297 @@
+use crate::{Error, HmacSigner, OAuth2Config, OAuth2TokenProvider, Result, Secret, TokenProvider};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use futures_util::{FutureExt, future::BoxFuture};
+use reqwest::{
+    Request,
+    header::{AUTHORIZATION, HeaderName, HeaderValue},
+};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+use zeroize::Zeroizing;
+
+const REDACTED: &str = "<redacted>";
+
+/// Adds credentials to every request made by an [`ApiClient`](crate::ApiClient)
+///
+/// Implement this trait for schemes not covered by [`Authentication`] and pass it to
+/// [`ApiClientBuilder::authentication`](crate::ApiClientBuilder::authentication).
+///
+/// # Example
+///
+/// ```
+/// # use futures_util::{FutureExt, future::BoxFuture};
+/// # use rest_json_client::{ApiClientBuilder, Authenticator, Error, Request, Result};
+/// # use rest_json_client::header::HeaderValue;
+/// #
+/// struct Tenant(&'static str);
+///
+/// impl Authenticator for Tenant {
+///     fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+///         request
+///             .headers_mut()
+///             .insert("X-Tenant", HeaderValue::from_static(self.0));
+///         futures_util::future::ok(()).boxed()
+///     }
+/// }
+///
+/// let client = ApiClientBuilder::new("https://www.paulmin.nl/lipl/api/v1/")
+///     .authentication(Tenant("paul"))
+///     .build()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub trait Authenticator: Send + Sync {
+    /// Add credentials to the request, called before every attempt
+    fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>>;
+
+    /// Forget cached credentials after an unauthorized response.
+    /// Return true if repeating the request can succeed with fresh credentials.
+    fn invalidate(&self) -> bool {
+        false
+    }
+
+    /// The credentials differ for every request, like a signature over the request.
+    /// Responses are then kept out of a [`ResponseCache`](crate::ResponseCache), because they could never be found again.
+    fn signs_every_request(&self) -> bool {
+        false
+    }
+}
+
+impl<A> Authenticator for Arc<A>
+where
+    A: Authenticator + ?Sized,
+{
+    fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+        self.as_ref().authenticate(request)
+    }
+
+    fn invalidate(&self) -> bool {
+        self.as_ref().invalidate()
+    }
+
+    fn signs_every_request(&self) -> bool {
+        self.as_ref().signs_every_request()
+    }
+}
+
+/// This library support two ways of authentication
+/// Either Basic of Bearer. A bearer token can also be obtained from a [`TokenProvider`]
+/// or from an OAuth2 token endpoint with [`Authentication::OAuth2`]. Api's using a key can use [`Authentication::ApiKey`],
+/// api's expecting signed requests [`Authentication::Hmac`].
+///
+/// Secrets are held in a [`Secret`], so they are zeroized on drop and redacted from the [`Debug`] output.
+#[derive(Clone, Default)]
+pub enum Authentication {
+    Basic(BasicAuthentication),
+    Bearer(Option<Secret>),
+    ApiKey {
+        location: ApiKeyLocation,
+        name: String,
+        value: Secret,
+    },
+    Hmac(HmacSigner),
+    Token(Arc<dyn TokenProvider>),
+    OAuth2(Arc<OAuth2TokenProvider>),
+    #[default]
+    None,
+}
+
+impl Authentication {
+    pub fn new_basic(username: &str, password: &str) -> Self {
+        Authentication::Basic(BasicAuthentication::new(username, password))
+    }
+    pub fn new_bearer(token: &str) -> Self {
+        Authentication::Bearer(Some(Secret::new(token)))
+    }
+    /// Send `value` as api key in the header or query parameter `name`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rest_json_client::{ApiKeyLocation, Authentication};
+    /// #
+    /// let auth = Authentication::new_api_key(ApiKeyLocation::Header, "X-Api-Key", "s3cr3t");
+    /// assert!(!format!("{auth:?}").contains("s3cr3t"));
+    /// ```
+    pub fn new_api_key(location: ApiKeyLocation, name: &str, value: &str) -> Self {
+        Authentication::ApiKey {
+            location,
+            name: name.to_owned(),
+            value: Secret::new(value),
+        }
+    }
+    /// Sign every request with `signer`
+    pub fn new_hmac(signer: HmacSigner) -> Self {
+        Authentication::Hmac(signer)
+    }
+    /// Use a bearer token provided by `provider`, which is consulted for every request
+    pub fn new_token_provider<P>(provider: P) -> Self
+    where
+        P: TokenProvider + 'static,
+    {
+        Authentication::Token(Arc::new(provider))
+    }
+    /// Use access tokens obtained with the OAuth2 client credentials grant
+    pub fn new_client_credentials(config: OAuth2Config) -> Result<Self> {
+        OAuth2TokenProvider::client_credentials(config)
+            .map(Arc::new)
+            .map(Authentication::OAuth2)
+    }
+    /// Use access tokens obtained with the OAuth2 refresh token grant
+    pub fn new_refresh_token(config: OAuth2Config, refresh_token: &str) -> Result<Self> {
+        OAuth2TokenProvider::refresh_token(config, refresh_token)
+            .map(Arc::new)
+            .map(Authentication::OAuth2)
+    }
+}
+
+impl Authenticator for Authentication {
+    fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+        match self {
+            Authentication::Basic(basic) => basic.authenticate(request),
+            Authentication::Bearer(Some(token)) => {
+                futures_util::future::ready(insert_bearer(request, token.expose())).boxed()
+            }
+            Authentication::ApiKey {
+                location,
+                name,
+                value,
+            } => futures_util::future::ready(location.apply(request, name, value.expose())).boxed(),
+            Authentication::Hmac(signer) => signer.authenticate(request),
+            Authentication::Token(provider) => authenticate_with_token(provider.as_ref(), request),
+            Authentication::OAuth2(provider) => provider.authenticate(request),
+            Authentication::Bearer(None) | Authentication::None => {
+                futures_util::future::ok(()).boxed()
+            }
+        }
+    }
+
+    fn invalidate(&self) -> bool {
+        match self {
+            Authentication::Token(provider) => {
+                provider.invalidate();
+                true
+            }
+            Authentication::OAuth2(provider) => Authenticator::invalidate(provider.as_ref()),
+            _ => false,
+        }
+    }
+
+    fn signs_every_request(&self) -> bool {
+        matches!(self, Authentication::Hmac(_))
+    }
+}
+
+/// Authenticate with a bearer token obtained from `provider`
+pub(crate) fn authenticate_with_token<'a>(
+    provider: &'a dyn TokenProvider,
+    request: &'a mut Request,
+) -> BoxFuture<'a, Result<()>> {
+    async move {
+        let token = provider.token().await?;
+        insert_bearer(request, token.expose())
+    }
+    .boxed()
+}
+
+impl Debug for Authentication {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Authentication::Basic(basic) => f.debug_tuple("Basic").field(basic).finish(),
+            Authentication::Bearer(token) => f
+                .debug_tuple("Bearer")
+                .field(&token.as_ref().map(|_| REDACTED))
+                .finish(),
+            Authentication::ApiKey { location, name, .. } => f
+                .debug_struct("ApiKey")
+                .field("location", location)
+                .field("name", name)
+                .field("value", &REDACTED)
+                .finish(),
+            Authentication::Hmac(signer) => f.debug_tuple("Hmac").field(signer).finish(),
+            Authentication::Token(_) => f.write_str("Token"),
+            Authentication::OAuth2(_) => f.write_str("OAuth2"),
+            Authentication::None => f.write_str("None"),
+        }
+    }
+}
+
+/// Where an api key is sent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiKeyLocation {
+    /// As a request header, for example `X-Api-Key`
+    Header,
+    /// As a query parameter, for example `api_key`
+    Query,
+}
+
+impl ApiKeyLocation {
+    fn apply(&self, request: &mut Request, name: &str, value: &str) -> Result<()> {
+        match self {
+            ApiKeyLocation::Header => {
+                let name = HeaderName::try_from(name)
+                    .map_err(|_| Error::Auth(format!("invalid api key header name {name}")))?;
+                request.headers_mut().insert(name, sensitive(value)?);
+            }
+            ApiKeyLocation::Query => {
+                request.url_mut().query_pairs_mut().append_pair(name, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct BasicAuthentication {
+    username: String,
+    password: Secret,
+}
+
+impl BasicAuthentication {
+    /// Create a new instance of BasicAuthentication with provided username and password
+    pub fn new<S: Into<String>>(username: S, password: S) -> Self {
+        Self {
+            username: username.into(),
+            password: Secret::new(password),
+        }
+    }
+
+    fn header_value(&self) -> Result<HeaderValue> {
+        let credentials = Zeroizing::new(format!("{}:{}", self.username, self.password.expose()));
+        let encoded = Zeroizing::new(BASE64_STANDARD.encode(credentials.as_bytes()));
+        sensitive(&Zeroizing::new(format!("Basic {}", encoded.as_str())))
+    }
+}
+
+impl Authenticator for BasicAuthentication {
+    fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+        let result = self.header_value().map(|value| {
+            request.headers_mut().insert(AUTHORIZATION, value);
+        });
+        futures_util::future::ready(result).boxed()
+    }
+}
+
+impl Debug for BasicAuthentication {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasicAuthentication")
+            .field("username", &self.username)
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
+fn insert_bearer(request: &mut Request, token: &str) -> Result<()> {
+    let value = sensitive(&Zeroizing::new(format!("Bearer {token}")))?;
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(())
+}
+
+/// Header value marked as sensitive, so that it is left out of the `Debug` output of the request
+fn sensitive(value: &str) -> Result<HeaderValue> {
+    let mut value = HeaderValue::from_str(value)
+        .map_err(|_| Error::Auth("credentials contain invalid characters".to_owned()))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
@@ -0,0 +1,519 @@
+use crate::{Middleware, Next, Result};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use bytes::Bytes;
+use futures_util::{
+    FutureExt,
+    future::{BoxFuture, ready},
+};
+use reqwest::{
+    Method, Request, Response, StatusCode,
+    header::{
+        AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, COOKIE, ETAG, HeaderMap, HeaderName,
+        HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE, LAST_MODIFIED,
+        PROXY_AUTHORIZATION, RANGE, VARY,
+    },
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const DEFAULT_CAPACITY: usize = 256;
+const DEFAULT_DISK_CAPACITY: usize = 1024;
+
+/// A stored response
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    /// When the response was received or last revalidated
+    pub stored: SystemTime,
+    /// The headers of the request named by the `Vary` header of the response
+    pub request_headers: HeaderMap,
+}
+
+impl CachedResponse {
+    fn etag(&self) -> Option<&HeaderValue> {
+        self.headers.get(ETAG)
+    }
+
+    fn last_modified(&self) -> Option<&HeaderValue> {
+        self.headers.get(LAST_MODIFIED)
+    }
+
+    fn is_fresh(&self) -> bool {
+        let directives = CacheControl::from(&self.headers);
+        match directives.max_age {
+            Some(max_age) if !directives.no_cache => self
+                .stored
+                .elapsed()
+                .is_ok_and(|age| age < Duration::from_secs(max_age)),
+            _ => false,
+        }
+    }
+
+    /// The request differs from the stored request in a header named by `Vary`.
+    /// Credentials are part of the key instead, so that they are not stored.
+    fn varies(&self, request: &HeaderMap) -> bool {
+        vary(&self.headers).is_none_or(|names| {
+            names.iter().any(|name| {
+                self.request_headers.get_all(name).iter().ne(request
+                    .get_all(name)
+                    .iter()
+                    .filter(|value| !is_credential(name, value)))
+            })
+        })
+    }
+
+    fn to_response(&self) -> Response {
+        let mut response = http::Response::new(self.body.clone());
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        Response::from(response)
+    }
+}
+
+/// Storage for cached responses, keyed by a digest of the request
+pub trait CacheStore: Send + Sync {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<CachedResponse>>;
+    fn put<'a>(&'a self, key: &'a str, response: CachedResponse) -> BoxFuture<'a, ()>;
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()>;
+}
+
+/// Keeps responses in memory for the lifetime of the store.
+/// When the store is full, the least recently used response is removed.
+pub struct MemoryStore {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+/// The responses with the tick at which they were last used
+#[derive(Default)]
+struct Entries {
+    tick: u64,
+    responses: HashMap<String, (u64, CachedResponse)>,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl MemoryStore {
+    /// A store for 256 responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store for `capacity` responses
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::default(),
+        }
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<CachedResponse>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.tick += 1;
+        let tick = entries.tick;
+        let response = entries.responses.get_mut(key).map(|(used, response)| {
+            *used = tick;
+            response.clone()
+        });
+        ready(response).boxed()
+    }
+
+    fn put<'a>(&'a self, key: &'a str, response: CachedResponse) -> BoxFuture<'a, ()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.tick += 1;
+        let tick = entries.tick;
+        entries.responses.insert(key.to_owned(), (tick, response));
+        while entries.responses.len() > self.capacity {
+            let oldest = entries
+                .responses
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.responses.remove(&oldest);
+            }
+        }
+        ready(()).boxed()
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()> {
+        self.entries.lock().unwrap().responses.remove(key);
+        ready(()).boxed()
+    }
+}
+
+/// Keeps every response in a json file in a directory, so that the cache survives a restart.
+/// Files that cannot be read or written are treated as missing.
+/// When the store is full, the responses that were stored longest ago are removed.
+pub struct DiskStore {
+    directory: PathBuf,
+    capacity: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+    stored: u64,
+    #[serde(default)]
+    request_headers: Vec<(String, String)>,
+}
+
+fn to_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+        .collect()
+}
+
+fn from_pairs(pairs: Vec<(String, String)>) -> Option<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        headers.append(
+            HeaderName::try_from(name).ok()?,
+            HeaderValue::try_from(value).ok()?,
+        );
+    }
+    Some(headers)
+}
+
+impl DiskStore {
+    /// Store 1024 responses in `directory`, which is created when needed
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        Self::with_capacity(directory, DEFAULT_DISK_CAPACITY)
+    }
+
+    /// Store `capacity` responses in `directory`, which is created when needed
+    pub fn with_capacity<P: Into<PathBuf>>(directory: P, capacity: usize) -> Self {
+        Self {
+            directory: directory.into(),
+            capacity,
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{key}.json"))
+    }
+
+    async fn read(&self, key: &str) -> Option<CachedResponse> {
+        let bytes = tokio::fs::read(self.path(key)).await.ok()?;
+        let stored = serde_json::from_slice::<StoredResponse>(&bytes).ok()?;
+        Some(CachedResponse {
+            status: StatusCode::from_u16(stored.status).ok()?,
+            headers: from_pairs(stored.headers)?,
+            body: BASE64_STANDARD.decode(stored.body).ok()?.into(),
+            stored: UNIX_EPOCH + Duration::from_secs(stored.stored),
+            request_headers: from_pairs(stored.request_headers)?,
+        })
+    }
+
+    async fn write(&self, key: &str, response: CachedResponse) -> std::io::Result<()> {
+        let stored = StoredResponse {
+            status: response.status.as_u16(),
+            headers: to_pairs(&response.headers),
+            body: BASE64_STANDARD.encode(&response.body),
+            stored: response
+                .stored
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            request_headers: to_pairs(&response.request_headers),
+        };
+        tokio::fs::create_dir_all(&self.directory).await?;
+        tokio::fs::write(self.path(key), serde_json::to_vec(&stored)?).await?;
+        self.evict().await
+    }
+
+    /// Remove the files written longest ago while there are more than `capacity`
+    async fn evict(&self) -> std::io::Result<()> {
+        let mut files = vec![];
+        let mut entries = tokio::fs::read_dir(&self.directory).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                files.push((entry.metadata().await?.modified()?, path));
+            }
+        }
+        if files.len() > self.capacity {
+            files.sort();
+            for (_, path) in &files[..files.len() - self.capacity] {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CacheStore for DiskStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<CachedResponse>> {
+        self.read(key).boxed()
+    }
+
+    fn put<'a>(&'a self, key: &'a str, response: CachedResponse) -> BoxFuture<'a, ()> {
+        self.write(key, response).map(|_| ()).boxed()
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> BoxFuture<'a, ()> {
+        tokio::fs::remove_file(self.path(key)).map(|_| ()).boxed()
+    }
+}
+
+/// The directives of a `Cache-Control` header relevant to a private client cache
+#[derive(Default)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<u64>,
+}
+
+impl From<&HeaderMap> for CacheControl {
+    fn from(headers: &HeaderMap) -> Self {
+        let mut directives = CacheControl::default();
+        let values = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok());
+        for directive in values.flat_map(|value| value.split(',')) {
+            let (name, argument) = directive
+                .trim()
+                .split_once('=')
+                .unwrap_or((directive.trim(), ""));
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => directives.no_store = true,
+                "no-cache" => directives.no_cache = true,
+                "max-age" => directives.max_age = argument.trim_matches('"').parse().ok(),
+                _ => {}
+            }
+        }
+        directives
+    }
+}
+
+/// Caches get responses and revalidates them with `ETag` and `Last-Modified`,
+/// added to a client with [`ApiClientBuilder::middleware`](crate::ApiClientBuilder::middleware)
+///
+/// A response is served from the cache without contacting the server while its `Cache-Control: max-age`
+/// has not passed. Otherwise the request is sent with `If-None-Match` or `If-Modified-Since`, and a
+/// `304 Not Modified` response is answered with the cached body, so that the caller gets the cached data.
+/// Responses with `Cache-Control: no-store` or `Vary: *` are never stored, and a cached response is only used
+/// for a request with the same values of the headers named by its `Vary` header. A successful put, post,
+/// patch or delete removes the cached response for the same url.
+///
+/// Only json responses are stored, so that downloads and event streams are not buffered in the cache.
+/// Requests for a range of the body are passed on without looking at the cache.
+///
+/// Responses are cached per set of credentials: the `Authorization`, `Proxy-Authorization` and `Cookie`
+/// headers, and every header marked sensitive, like an api key, are part of the cache key.
+/// Requests signed with [`HmacSigner`](crate::HmacSigner) carry a new signature every time,
+/// so they bypass the cache, see [`Authenticator::signs_every_request`](crate::Authenticator::signs_every_request).
+/// Responses stored with a bearer token that has since been refreshed are no longer found,
+/// they are removed when the store is full.
+///
+/// Add the cache as the first middleware, so that fresh responses skip the other layers.
+///
+/// # Example
+///
+/// ```
+/// # use rest_json_client::{ApiClientBuilder, Error, ResponseCache};
+/// #
+/// let client = ApiClientBuilder::new("https://www.paulmin.nl/lipl/api/v1/")
+///     .middleware(ResponseCache::memory())
+///     .build()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone)]
+pub struct ResponseCache {
+    store: Arc<dyn CacheStore>,
+}
+
+impl ResponseCache {
+    pub fn new<S>(store: S) -> Self
+    where
+        S: CacheStore + 'static,
+    {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+
+    /// Cache in a [`MemoryStore`]
+    pub fn memory() -> Self {
+        Self::new(MemoryStore::new())
+    }
+
+    /// Cache in a [`DiskStore`] in `directory`
+    pub fn disk<P: Into<PathBuf>>(directory: P) -> Self {
+        Self::new(DiskStore::new(directory))
+    }
+
+    async fn get(&self, mut request: Request, next: Next<'_>) -> Result<Response> {
+        let key = key(&Method::GET, &request);
+        if CacheControl::from(request.headers()).no_store {
+            return next.run(request).await;
+        }
+        let cached = self
+            .store
+            .get(&key)
+            .await
+            .filter(|cached| !cached.varies(request.headers()));
+        let request_headers = request.headers().clone();
+        if let Some(cached) = &cached {
+            if cached.is_fresh() && !CacheControl::from(request.headers()).no_cache {
+                return Ok(cached.to_response());
+            }
+            let headers = request.headers_mut();
+            if let Some(etag) = cached.etag() {
+                headers.insert(IF_NONE_MATCH, etag.clone());
+            } else if let Some(last_modified) = cached.last_modified() {
+                headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+            }
+        }
+
+        let response = next.run(request).await?;
+        match cached {
+            Some(mut cached) if response.status() == StatusCode::NOT_MODIFIED => {
+                for name in [CACHE_CONTROL, ETAG, LAST_MODIFIED] {
+                    if let Some(value) = response.headers().get(&name) {
+                        cached.headers.insert(name, value.clone());
+                    }
+                }
+                cached.stored = SystemTime::now();
+                let response = cached.to_response();
+                self.store.put(&key, cached).await;
+                Ok(response)
+            }
+            _ if response.status() == StatusCode::OK && is_cacheable(response.headers()) => {
+                let mut varied = HeaderMap::new();
+                for name in vary(response.headers()).unwrap_or_default() {
+                    let values = request_headers.get_all(&name).into_iter();
+                    for value in values.filter(|value| !is_credential(&name, value)) {
+                        varied.append(name.clone(), value.clone());
+                    }
+                }
+                let cached = CachedResponse {
+                    status: response.status(),
+                    headers: response.headers().clone(),
+                    body: response.bytes().await?,
+                    stored: SystemTime::now(),
+                    request_headers: varied,
+                };
+                let response = cached.to_response();
+                self.store.put(&key, cached).await;
+                Ok(response)
+            }
+            Some(_) if response.status().is_success() => {
+                self.store.remove(&key).await;
+                Ok(response)
+            }
+            _ => Ok(response),
+        }
+    }
+
+    async fn invalidate(&self, request: Request, next: Next<'_>) -> Result<Response> {
+        let key = key(&Method::GET, &request);
+        let response = next.run(request).await?;
+        if response.status().is_success() {
+            self.store.remove(&key).await;
+        }
+        Ok(response)
+    }
+}
+
+impl Middleware for ResponseCache {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        match *request.method() {
+            _ if next.is_signed() => next.run(request),
+            Method::GET
+                if !request.headers().contains_key(RANGE)
+                    && !request.headers().contains_key(IF_RANGE) =>
+            {
+                self.get(request, next).boxed()
+            }
+            Method::GET => next.run(request),
+            Method::HEAD | Method::OPTIONS | Method::TRACE => next.run(request),
+            _ => self.invalidate(request, next).boxed(),
+        }
+    }
+}
+
+fn is_cacheable(headers: &HeaderMap) -> bool {
+    let directives = CacheControl::from(headers);
+    !directives.no_store
+        && is_json(headers)
+        && vary(headers).is_some()
+        && (directives.max_age.is_some()
+            || headers.contains_key(ETAG)
+            || headers.contains_key(LAST_MODIFIED))
+}
+
+/// The content type is `application/json` or ends with `+json`
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase())
+        .is_some_and(|mime| mime == "application/json" || mime.ends_with("+json"))
+}
+
+/// The request headers named by the `Vary` header of a response, `None` for `Vary: *`
+fn vary(headers: &HeaderMap) -> Option<Vec<HeaderName>> {
+    let mut names = Vec::new();
+    let values = headers
+        .get_all(VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok());
+    for name in values.flat_map(|value| value.split(',')).map(str::trim) {
+        if name == "*" {
+            return None;
+        }
+        if let Ok(name) = HeaderName::try_from(name) {
+            names.push(name);
+        }
+    }
+    Some(names)
+}
+
+fn is_credential(name: &HeaderName, value: &HeaderValue) -> bool {
+    value.is_sensitive() || [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE].contains(name)
+}
+
+/// Digest of the method, url and credentials, so that users of a shared store do not see each other's responses
+fn key(method: &Method, request: &Request) -> String {
+    let mut credentials = request
+        .headers()
+        .iter()
+        .filter(|(name, value)| is_credential(name, value))
+        .collect::<Vec<_>>();
+    credentials.sort_by_key(|(name, _)| name.as_str());
+
+    let mut digest = Sha256::new();
+    digest.update(method.as_str());
+    digest.update(b" ");
+    digest.update(request.url().as_str());
+    for (name, value) in credentials {
+        digest.update(b"\n");
+        digest.update(name.as_str());
+        digest.update(b": ");
+        digest.update(value.as_bytes());
+    }
+    hex::encode(digest.finalize())
+}
@@ -0,0 +1,194 @@
+use reqwest::{
+    Request,
+    header::{HeaderName, HeaderValue},
+};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+pub(crate) const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+const TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+pub(crate) const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// A W3C trace context, sent in the `traceparent` and `tracestate` headers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceParent {
+    pub trace_id: u128,
+    pub parent_id: u64,
+    pub sampled: bool,
+    pub tracestate: Option<String>,
+}
+
+impl TraceParent {
+    /// Start a new sampled trace
+    pub fn generate() -> Self {
+        Self {
+            trace_id: fastrand::u128(1..),
+            parent_id: fastrand::u64(1..),
+            sampled: true,
+            tracestate: None,
+        }
+    }
+
+    /// Parse the value of a `traceparent` header, for example one received by a server
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+        let (version, trace_id, parent_id, flags) =
+            (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        if version != "00"
+            || parts.next().is_some()
+            || trace_id.len() != 32
+            || parent_id.len() != 16
+            || flags.len() != 2
+        {
+            return None;
+        }
+        let trace_id = u128::from_str_radix(trace_id, 16)
+            .ok()
+            .filter(|id| *id != 0)?;
+        let parent_id = u64::from_str_radix(parent_id, 16)
+            .ok()
+            .filter(|id| *id != 0)?;
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+        Some(Self {
+            trace_id,
+            parent_id,
+            sampled: flags & 1 == 1,
+            tracestate: None,
+        })
+    }
+
+    /// Attach the value of a `tracestate` header
+    pub fn with_tracestate(mut self, tracestate: &str) -> Self {
+        self.tracestate = Some(tracestate.to_owned());
+        self
+    }
+
+    /// The context for an outgoing request: the same trace with a new parent id
+    pub fn child(&self) -> Self {
+        Self {
+            parent_id: fastrand::u64(1..),
+            ..self.clone()
+        }
+    }
+
+    /// The trace id as 32 lowercase hexadecimal digits
+    pub fn trace_id_hex(&self) -> String {
+        format!("{:032x}", self.trace_id)
+    }
+
+    /// The value of the `traceparent` header
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{:016x}-{:02x}",
+            self.trace_id_hex(),
+            self.parent_id,
+            u8::from(self.sampled)
+        )
+    }
+}
+
+/// Where the trace context sent with every request comes from,
+/// configured with [`ApiClientBuilder::trace_context`](crate::ApiClientBuilder::trace_context)
+///
+/// The context is not taken from the current `tracing` span, because a span has no W3C trace id.
+/// An application that propagates traces, for example with OpenTelemetry, returns its current
+/// context from the function given to [`TraceContext::current`].
+///
+/// # Example
+///
+/// Continue the trace of an incoming request
+///
+/// ```
+/// # use rest_json_client::{ApiClientBuilder, Error, TraceContext, TraceParent};
+/// #
+/// let incoming = TraceParent::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01");
+/// let client = ApiClientBuilder::new("https://www.paulmin.nl/lipl/api/v1/")
+///     .trace_context(TraceContext::current(move || incoming.clone()))
+///     .build()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone)]
+pub enum TraceContext {
+    /// Start a new trace for every call
+    Generated,
+    /// Continue the trace returned by the function, usually the trace the application is handling.
+    /// Every call gets a new parent id. A new trace is started when the function returns `None`.
+    Current(Arc<dyn Fn() -> Option<TraceParent> + Send + Sync>),
+}
+
+impl TraceContext {
+    pub fn current<F>(f: F) -> Self
+    where
+        F: Fn() -> Option<TraceParent> + Send + Sync + 'static,
+    {
+        TraceContext::Current(Arc::new(f))
+    }
+
+    fn trace_parent(&self) -> TraceParent {
+        match self {
+            TraceContext::Generated => TraceParent::generate(),
+            TraceContext::Current(current) => current()
+                .map(|trace_parent| trace_parent.child())
+                .unwrap_or_else(TraceParent::generate),
+        }
+    }
+
+    /// Add the `traceparent` and `tracestate` headers, unless the request already has a `traceparent`
+    pub(crate) fn inject(&self, request: &mut Request) {
+        if request.headers().contains_key(TRACEPARENT) {
+            return;
+        }
+        let trace_parent = self.trace_parent();
+        let headers = request.headers_mut();
+        headers.insert(
+            TRACEPARENT,
+            HeaderValue::try_from(trace_parent.traceparent()).expect("hex is a valid header value"),
+        );
+        if let Some(tracestate) = trace_parent
+            .tracestate
+            .and_then(|tracestate| HeaderValue::try_from(tracestate).ok())
+        {
+            headers.insert(TRACESTATE, tracestate);
+        }
+    }
+}
+
+impl Debug for TraceContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceContext::Generated => f.write_str("Generated"),
+            TraceContext::Current(_) => f.write_str("Current"),
+        }
+    }
+}
+
+/// Name of the request id header, stored in the response so that [`StatusError`](crate::StatusError) can find the echoed id
+#[derive(Clone)]
+pub(crate) struct RequestIdHeader(pub(crate) HeaderName);
+
+/// Add a generated request id, unless the request already has one. Retries of a call send the same id.
+pub(crate) fn inject_request_id(request: &mut Request, name: &HeaderName) {
+    if !request.headers().contains_key(name) {
+        request.headers_mut().insert(
+            name.clone(),
+            HeaderValue::try_from(uuid_v4()).expect("hex is a valid header value"),
+        );
+    }
+}
+
+/// A random uuid in the usual hyphenated form
+fn uuid_v4() -> String {
+    let random = fastrand::u128(..);
+    let uuid = random & !(0xf << 76) & !(0x3 << 62) | (0x4 << 76) | (0x2 << 62);
+    let hex = format!("{uuid:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
@@ -0,0 +1,221 @@
+use crate::{Authentication, Error, Result, Secret};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Contents of a credentials file, either a token or a username and password
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CredentialsFile {
+    Token { token: String },
+    Basic { username: String, password: String },
+}
+
+impl Authentication {
+    /// Read credentials from the environment variables `{prefix}_TOKEN` for bearer authentication,
+    /// or `{prefix}_USERNAME` and `{prefix}_PASSWORD` for basic authentication.
+    /// Fails with [`Error::Auth`] if neither is set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rest_json_client::{ApiClientBuilder, Authentication, Error};
+    /// #
+    /// // reads LIPL_TOKEN or LIPL_USERNAME and LIPL_PASSWORD
+    /// let client = ApiClientBuilder::new("https://www.paulmin.nl/lipl/api/v1/")
+    ///     .authentication(Authentication::from_env("LIPL")?)
+    ///     .build()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn from_env(prefix: &str) -> Result<Self> {
+        env_credentials(prefix)?.ok_or_else(|| {
+            Error::Auth(format!(
+                "environment variables {prefix}_TOKEN or {prefix}_USERNAME and {prefix}_PASSWORD are not set"
+            ))
+        })
+    }
+
+    /// Read the login and password for `host` from the netrc file.
+    /// The file is `$NETRC` if set, otherwise `.netrc` in the home directory.
+    pub fn from_netrc(host: &str) -> Result<Self> {
+        let path = netrc_path().ok_or_else(|| Error::Auth("no home directory".to_owned()))?;
+        Self::from_netrc_file(path, host)
+    }
+
+    /// Read the login and password for `host` from a file in netrc format.
+    /// The `default` entry is used when the file has no entry for `host`.
+    pub fn from_netrc_file<P: AsRef<Path>>(path: P, host: &str) -> Result<Self> {
+        let path = path.as_ref();
+        netrc_credentials(path, host)?.ok_or_else(|| {
+            Error::Auth(format!(
+                "no credentials for {host} in netrc file {}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Read credentials from a json file, containing either `{"token": "..."}`
+    /// or `{"username": "...", "password": "..."}`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = Zeroizing::new(read(path)?.ok_or_else(|| {
+            Error::Auth(format!("credentials file {} not found", path.display()))
+        })?);
+        let credentials = serde_json::from_str::<CredentialsFile>(&contents).map_err(|error| {
+            Error::Auth(format!(
+                "invalid credentials file {}: {error}",
+                path.display()
+            ))
+        })?;
+        Ok(match credentials {
+            CredentialsFile::Token { token } => Authentication::Bearer(Some(Secret::new(token))),
+            CredentialsFile::Basic { username, password } => {
+                Authentication::new_basic(&username, &Zeroizing::new(password))
+            }
+        })
+    }
+
+    /// Find credentials in the order used by [`ApiClientBuilder::discover_authentication`](crate::ApiClientBuilder::discover_authentication):
+    ///
+    /// 1. the environment variables read by [`Authentication::from_env`]
+    /// 2. the json file named by the environment variable `{prefix}_CREDENTIALS_FILE`
+    /// 3. the netrc entry for `host`
+    ///
+    /// Incomplete or unreadable credentials are reported instead of skipped.
+    pub fn discover(prefix: &str, host: &str) -> Result<Self> {
+        if let Some(authentication) = env_credentials(prefix)? {
+            return Ok(authentication);
+        }
+        if let Some(path) = var(&format!("{prefix}_CREDENTIALS_FILE")) {
+            return Self::from_file(path.as_str());
+        }
+        if let Some(path) = netrc_path()
+            && let Some(authentication) = netrc_credentials(&path, host)?
+        {
+            return Ok(authentication);
+        }
+        Err(Error::Auth(format!(
+            "no credentials found for {host} in the environment or netrc"
+        )))
+    }
+}
+
+fn var(name: &str) -> Option<Zeroizing<String>> {
+    std::env::var(name).ok().map(Zeroizing::new)
+}
+
+fn env_credentials(prefix: &str) -> Result<Option<Authentication>> {
+    if let Some(token) = var(&format!("{prefix}_TOKEN")) {
+        return Ok(Some(Authentication::new_bearer(&token)));
+    }
+    match (
+        var(&format!("{prefix}_USERNAME")),
+        var(&format!("{prefix}_PASSWORD")),
+    ) {
+        (Some(username), Some(password)) => {
+            Ok(Some(Authentication::new_basic(&username, &password)))
+        }
+        (Some(_), None) => Err(Error::Auth(format!(
+            "environment variable {prefix}_PASSWORD is not set"
+        ))),
+        (None, Some(_)) => Err(Error::Auth(format!(
+            "environment variable {prefix}_USERNAME is not set"
+        ))),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Contents of the file, `None` if it does not exist
+fn read(path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(Error::Auth(format!(
+            "cannot read {}: {error}",
+            path.display()
+        ))),
+    }
+}
+
+fn netrc_path() -> Option<PathBuf> {
+    std::env::var_os("NETRC").map(PathBuf::from).or_else(|| {
+        std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(|home| Path::new(&home).join(".netrc"))
+    })
+}
+
+fn netrc_credentials(path: &Path, host: &str) -> Result<Option<Authentication>> {
+    let Some(contents) = read(path)?.map(Zeroizing::new) else {
+        return Ok(None);
+    };
+    let entry = netrc_entry(&contents, host);
+    match entry {
+        Some(NetrcEntry {
+            login: Some(login),
+            password: Some(password),
+        }) => Ok(Some(Authentication::new_basic(login, password))),
+        Some(_) => Err(Error::Auth(format!(
+            "netrc entry for {host} in {} lacks a login or password",
+            path.display()
+        ))),
+        None => Ok(None),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Machine {
+    Host,
+    Default,
+    Other,
+}
+
+#[derive(Default)]
+struct NetrcEntry<'a> {
+    login: Option<&'a str>,
+    password: Option<&'a str>,
+}
+
+/// The entry for `host` or else the `default` entry. Macro definitions are skipped.
+fn netrc_entry<'a>(contents: &'a str, host: &str) -> Option<NetrcEntry<'a>> {
+    let mut tokens = Vec::new();
+    let mut in_macro = false;
+    for line in contents.lines() {
+        if in_macro {
+            in_macro = !line.trim().is_empty();
+        } else {
+            tokens.extend(line.split_whitespace().take_while(|word| *word != "macdef"));
+            in_macro = line.split_whitespace().any(|word| word == "macdef");
+        }
+    }
+
+    let mut entries: Vec<(Machine, NetrcEntry<'a>)> = Vec::new();
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" => {
+                let machine = if tokens.next() == Some(host) {
+                    Machine::Host
+                } else {
+                    Machine::Other
+                };
+                entries.push((machine, NetrcEntry::default()));
+            }
+            "default" => entries.push((Machine::Default, NetrcEntry::default())),
+            "login" | "password" | "account" => {
+                let value = tokens.next();
+                if let Some((_, entry)) = entries.last_mut() {
+                    match token {
+                        "login" => entry.login = value,
+                        "password" => entry.password = value,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let position = |machine| entries.iter().position(|(kind, _)| *kind == machine);
+    let index = position(Machine::Host).or_else(|| position(Machine::Default))?;
+    Some(entries.swap_remove(index).1)
+}
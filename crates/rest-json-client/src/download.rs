@@ -0,0 +1,271 @@
+use crate::{ApiClient, Error, Result, error_for_status};
+use futures_util::StreamExt;
+use reqwest::{
+    Method, Response, StatusCode,
+    header::{ACCEPT_ENCODING, CONTENT_RANGE, ETAG, HeaderValue, IF_RANGE, LAST_MODIFIED, RANGE},
+};
+use std::{io, sync::Arc};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+const IDENTITY: HeaderValue = HeaderValue::from_static("identity");
+const DEFAULT_MAX_RESUMES: u32 = 3;
+
+/// How much of a download has been written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of bytes written, including the bytes written before the download was resumed
+    pub received: u64,
+    /// Size of the whole file, if the server sent it
+    pub total: Option<u64>,
+}
+
+/// Options for [`ApiClient::download_with`]
+///
+/// The body is requested without compression, so that its size can be verified against `Content-Length`.
+/// When the connection is lost before the whole body is received, the download is resumed with a `Range` request
+/// for the missing bytes. `If-Range` makes sure the missing bytes belong to the same version of the file.
+///
+/// # Example
+///
+/// Continue downloading to a partially written file, reporting progress through a channel
+///
+/// ```no_run
+/// # use rest_json_client::{ApiClientBuilder, Download, Error};
+/// # use tokio::sync::watch;
+/// #
+/// # tokio_test::block_on(async {
+///     let mut file = tokio::fs::OpenOptions::new()
+///         .append(true)
+///         .create(true)
+///         .open("roodkapje.pdf")
+///         .await?;
+///     let written = file.metadata().await?.len();
+///     let (sender, mut receiver) = watch::channel(None);
+///     tokio::spawn(async move {
+///         while receiver.changed().await.is_ok() {
+///             if let Some(progress) = *receiver.borrow() {
+///                 println!("{progress:?}");
+///             }
+///         }
+///     });
+///
+///     let base = "https://www.paulmin.nl/lipl/api/v1/";
+///     ApiClientBuilder::new(base)
+///         .build()?
+///         .download_with(
+///             "sheet/roodkapje.pdf",
+///             &mut file,
+///             Download::new()
+///                 .resume_from(written)
+///                 .progress(move |progress| {
+///                     sender.send_replace(Some(progress));
+///                 }),
+///         )
+///         .await?;
+/// #     Ok::<(), Error>(())
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct Download {
+    offset: u64,
+    max_resumes: u32,
+    progress: Option<Arc<dyn Fn(Progress) + Send + Sync>>,
+}
+
+impl Default for Download {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            max_resumes: DEFAULT_MAX_RESUMES,
+            progress: None,
+        }
+    }
+}
+
+impl Download {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The first `offset` bytes have already been written, only the rest is requested
+    pub fn resume_from(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// How often a download that was interrupted is resumed, 3 by default
+    pub fn max_resumes(mut self, max_resumes: u32) -> Self {
+        self.max_resumes = max_resumes;
+        self
+    }
+
+    /// Call `progress` after every chunk that is written
+    pub fn progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    fn report(&self, received: u64, total: Option<u64>) {
+        if let Some(progress) = &self.progress {
+            progress(Progress { received, total });
+        }
+    }
+}
+
+/// The parsed `Content-Range` header of a response, where `*` is `None`
+struct ContentRange {
+    start: Option<u64>,
+    total: Option<u64>,
+}
+
+impl ContentRange {
+    fn from_response(response: &Response) -> Option<Self> {
+        let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+        let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+        let start = match range {
+            "*" => None,
+            range => Some(range.split_once('-')?.0.parse().ok()?),
+        };
+        let total = match total {
+            "*" => None,
+            total => Some(total.parse().ok()?),
+        };
+        Some(Self { start, total })
+    }
+}
+
+/// A strong validator of the response, which identifies the version of the file
+fn validator(response: &Response) -> Option<HeaderValue> {
+    let headers = response.headers();
+    headers
+        .get(ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(LAST_MODIFIED))
+        .cloned()
+}
+
+fn invalid_data(message: String) -> Error {
+    io::Error::new(io::ErrorKind::InvalidData, message).into()
+}
+
+pub(crate) async fn download<W>(
+    client: &ApiClient,
+    uri: &str,
+    writer: &mut W,
+    options: Download,
+) -> Result<u64>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let result = transfer(client, uri, writer, options).await;
+    // also after an error, so that the bytes written so far can be used to resume
+    let flushed = writer.flush().await;
+    let received = result?;
+    flushed?;
+    Ok(received)
+}
+
+async fn transfer<W>(
+    client: &ApiClient,
+    uri: &str,
+    writer: &mut W,
+    options: Download,
+) -> Result<u64>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut received = options.offset;
+    let mut version: Option<HeaderValue> = None;
+    let mut resumes = 0;
+    loop {
+        let mut request = client
+            .request(Method::GET, uri)
+            .header(ACCEPT_ENCODING, IDENTITY);
+        if received > 0 {
+            request = request.header(RANGE, format!("bytes={received}-"));
+            if let Some(version) = &version {
+                request = request.header(IF_RANGE, version.clone());
+            }
+        }
+        let response = request.send().await?;
+        let range = ContentRange::from_response(&response);
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE
+            && range
+                .as_ref()
+                .is_some_and(|range| range.total == Some(received))
+        {
+            // the whole file was written before
+            return Ok(received);
+        }
+        let response = error_for_status(response).await?;
+
+        // offset in the file of the first byte of the body
+        let (mut position, total) = if response.status() == StatusCode::PARTIAL_CONTENT {
+            match range {
+                Some(ContentRange {
+                    start: Some(start),
+                    total,
+                }) if start == received => (start, total),
+                _ => {
+                    return Err(invalid_data(format!(
+                        "unexpected range for bytes={received}-"
+                    )));
+                }
+            }
+        } else {
+            if received > 0 && version.is_some() && validator(&response) != version {
+                return Err(invalid_data(format!("{uri} changed during the download")));
+            }
+            (0, response.content_length())
+        };
+        version = version.or_else(|| validator(&response));
+
+        let mut body = response.bytes_stream();
+        let mut interrupted = None;
+        while let Some(chunk) = body.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(error) => {
+                    interrupted = Some(Error::from(error));
+                    break;
+                }
+            };
+            let start = position;
+            position += chunk.len() as u64;
+            // a server that ignores the range sends the bytes that were written before again
+            if position > received {
+                let skip = received.saturating_sub(start) as usize;
+                writer.write_all(&chunk[skip..]).await?;
+                received = position;
+                options.report(received, total);
+            }
+        }
+
+        match total {
+            Some(total) if received > total => {
+                return Err(invalid_data(format!(
+                    "received {received} bytes, more than the {total} announced"
+                )));
+            }
+            Some(total) if received == total => break,
+            None if interrupted.is_none() => break,
+            _ => {}
+        }
+        if resumes == options.max_resumes {
+            // without an error the connection was closed before `total` bytes were received
+            return Err(interrupted.unwrap_or_else(|| {
+                let total = total.unwrap_or_default();
+                io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("received {received} of {total} bytes"),
+                )
+                .into()
+            }));
+        }
+        resumes += 1;
+    }
+    Ok(received)
+}
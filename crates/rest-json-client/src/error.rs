@@ -0,0 +1,193 @@
+use crate::ProblemDetails;
+use reqwest::{
+    StatusCode,
+    header::{HeaderMap, HeaderName},
+};
+use std::fmt::{Display, Formatter};
+
+/// Maximum number of characters of an offending payload kept in [`Error::Deserialize`]
+const SNIPPET_LENGTH: usize = 256;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// All the ways a call made with an [`ApiClient`](crate::ApiClient) can fail
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The request could not be sent or the response could not be received
+    #[error("Transport: {0}")]
+    Transport(reqwest::Error),
+    /// The connect, read or overall timeout elapsed before the request completed
+    #[error("Timeout: {0}")]
+    Timeout(reqwest::Error),
+    /// The server responded with a non success status code
+    #[error("{0}")]
+    Status(Box<StatusError>),
+    /// The response body could not be deserialized into the requested type
+    #[error("Deserialize: {source} in payload {snippet:?}")]
+    Deserialize {
+        source: serde_json::Error,
+        snippet: String,
+    },
+    /// The url for the request could not be constructed, or it would leave the prefix of the client
+    #[error("Url: {0}")]
+    Url(String),
+    /// Authentication failed or could not be performed
+    #[error("Authentication: {0}")]
+    Auth(String),
+    /// The request body could not be serialized
+    #[error("Serialize: {0}")]
+    Serialize(serde_json::Error),
+    /// A file could not be read or written
+    #[error("Io: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    pub(crate) fn deserialize(source: serde_json::Error, payload: &[u8]) -> Self {
+        Error::Deserialize {
+            source,
+            snippet: String::from_utf8_lossy(payload)
+                .chars()
+                .take(SNIPPET_LENGTH)
+                .collect(),
+        }
+    }
+
+    /// The status code of the response if the error was caused by a non success status code
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Status(error) => Some(error.status()),
+            Error::Transport(error) => error.status(),
+            _ => None,
+        }
+    }
+
+    /// True if the error was caused by an elapsed timeout
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Error::Timeout(_))
+    }
+
+    /// Short name of the variant, used to label traces and metrics
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Error::Transport(_) => "transport",
+            Error::Timeout(_) => "timeout",
+            Error::Status(_) => "status",
+            Error::Deserialize { .. } => "deserialize",
+            Error::Url(_) => "url",
+            Error::Auth(_) => "auth",
+            Error::Serialize(_) => "serialize",
+            Error::Io(_) => "io",
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    /// The query string and user info of the url are removed from the error,
+    /// because they can hold an api key or a password
+    fn from(error: reqwest::Error) -> Self {
+        let error = match error.url() {
+            Some(url)
+                if url.query().is_some() || url.has_authority() && !url.username().is_empty() =>
+            {
+                let mut url = url.clone();
+                url.set_query(None);
+                let _ = url.set_username("");
+                let _ = url.set_password(None);
+                error.with_url(url)
+            }
+            _ => error,
+        };
+        if error.is_timeout() {
+            Error::Timeout(error)
+        } else {
+            Error::Transport(error)
+        }
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(error: url::ParseError) -> Self {
+        Error::Url(error.to_string())
+    }
+}
+
+impl From<StatusError> for Error {
+    fn from(error: StatusError) -> Self {
+        Error::Status(Box::new(error))
+    }
+}
+
+/// Details of a response with a non success status code
+#[derive(Debug)]
+pub struct StatusError {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: String,
+    problem: Option<ProblemDetails>,
+    request_id: Option<String>,
+}
+
+impl StatusError {
+    pub(crate) fn new(
+        status: StatusCode,
+        headers: HeaderMap,
+        body: String,
+        request_id_header: &HeaderName,
+    ) -> Self {
+        let problem = ProblemDetails::from_response(&headers, &body);
+        let request_id = headers
+            .get(request_id_header)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        Self {
+            status,
+            headers,
+            body,
+            problem,
+            request_id,
+        }
+    }
+
+    /// The status code returned by the server
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The headers returned by the server
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The raw body returned by the server
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// The problem details if the server responded with `application/problem+json`
+    pub fn problem(&self) -> Option<&ProblemDetails> {
+        self.problem.as_ref()
+    }
+
+    /// The request id echoed by the server, in `X-Request-Id` or the header configured with
+    /// [`ApiClientBuilder::request_id_header`](crate::ApiClientBuilder::request_id_header)
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+}
+
+impl Display for StatusError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Status: {}", self.status)?;
+        if let Some(problem) = &self.problem {
+            write!(f, ", {problem}")?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request id {request_id})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for StatusError {}
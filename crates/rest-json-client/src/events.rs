@@ -0,0 +1,247 @@
+use crate::{ApiClient, Error, Result, error_for_status};
+use bytes::{Buf, Bytes, BytesMut};
+use futures_util::{Stream, StreamExt, stream, stream::BoxStream};
+use reqwest::{
+    Method, StatusCode,
+    header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE, HeaderValue},
+};
+use serde::de::DeserializeOwned;
+use std::{io, time::Duration};
+
+const TEXT_EVENT_STREAM: HeaderValue = HeaderValue::from_static("text/event-stream");
+const NO_CACHE: HeaderValue = HeaderValue::from_static("no-cache");
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+const DEFAULT_RETRY: Duration = Duration::from_secs(3);
+const MESSAGE: &str = "message";
+const MAX_RECONNECTS: u32 = 3;
+
+/// An event received from a [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html)
+/// stream, returned by [`ApiClient::events`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event<T> {
+    /// The last event id sent by the server, also for events without an `id` field
+    pub id: Option<String>,
+    /// The name of the event, `message` when the server did not send an `event` field
+    pub event: String,
+    /// The json in the `data` fields of the event
+    pub data: T,
+}
+
+/// An event before its data is deserialized
+struct RawEvent {
+    id: Option<String>,
+    event: String,
+    data: String,
+}
+
+/// Splits an event stream into events while it is being received
+#[derive(Default)]
+struct Parser {
+    buffer: BytesMut,
+    /// The previous line ended with a carriage return, so a line feed that follows it is part of the line ending
+    skip_line_feed: bool,
+    event: String,
+    data: String,
+    last_event_id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl Parser {
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Forget a partially received event, keeping the last event id and the retry delay
+    fn reset(&mut self) {
+        self.buffer.clear();
+        self.skip_line_feed = false;
+        self.event.clear();
+        self.data.clear();
+    }
+
+    fn line(&mut self) -> Option<String> {
+        if let Some(&byte) = self.buffer.first() {
+            if self.skip_line_feed && byte == b'\n' {
+                self.buffer.advance(1);
+            }
+            self.skip_line_feed = false;
+        }
+        let end = self
+            .buffer
+            .iter()
+            .position(|byte| matches!(byte, b'\r' | b'\n'))?;
+        let line = self.buffer.split_to(end);
+        self.skip_line_feed = self.buffer[0] == b'\r';
+        self.buffer.advance(1);
+        Some(String::from_utf8_lossy(&line).into_owned())
+    }
+
+    /// The next complete event, or `None` when more of the stream is needed
+    fn next(&mut self) -> Option<RawEvent> {
+        while let Some(line) = self.line() {
+            if line.is_empty() {
+                if let Some(event) = self.dispatch() {
+                    return Some(event);
+                }
+                continue;
+            }
+            let (name, value) = line.split_once(':').unwrap_or((&line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match name {
+                "event" => self.event = value.to_owned(),
+                "data" => {
+                    self.data.push_str(value);
+                    self.data.push('\n');
+                }
+                "id" if !value.contains('\0') => {
+                    self.last_event_id = Some(value.to_owned()).filter(|id| !id.is_empty());
+                }
+                "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                    self.retry = value.parse().ok().map(Duration::from_millis);
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<RawEvent> {
+        let event = std::mem::take(&mut self.event);
+        let mut data = std::mem::take(&mut self.data);
+        data.pop()?;
+        Some(RawEvent {
+            id: self.last_event_id.clone(),
+            event: if event.is_empty() {
+                MESSAGE.to_owned()
+            } else {
+                event
+            },
+            data,
+        })
+    }
+}
+
+/// The connection to the server, reopened when it is closed or lost
+struct EventSource<'a> {
+    client: &'a ApiClient,
+    uri: String,
+    body: Option<BoxStream<'static, reqwest::Result<Bytes>>>,
+    parser: Parser,
+    reconnect: bool,
+    /// A connection has been opened before, so a failing connection may be temporary
+    opened: bool,
+    /// Connections that failed since the last one that was opened
+    failures: u32,
+    done: bool,
+}
+
+impl EventSource<'_> {
+    /// Open the event stream, `None` when the server responds with `204 No Content` to tell the client to stop
+    async fn connect(&mut self) -> Result<Option<BoxStream<'static, reqwest::Result<Bytes>>>> {
+        if self.reconnect {
+            tokio::time::sleep(self.parser.retry.unwrap_or(DEFAULT_RETRY)).await;
+        }
+        self.reconnect = true;
+        let mut request = self
+            .client
+            .request(Method::GET, &self.uri)
+            .header(ACCEPT, TEXT_EVENT_STREAM)
+            .header(CACHE_CONTROL, NO_CACHE);
+        if let Some(id) = &self.parser.last_event_id {
+            request = request.header(LAST_EVENT_ID, id.as_str());
+        }
+        let response = error_for_status(request.send().await?).await?;
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        if !mime.eq_ignore_ascii_case(TEXT_EVENT_STREAM.to_str().unwrap_or_default()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is not an event stream but {content_type:?}", self.uri),
+            )
+            .into());
+        }
+        Ok(Some(response.bytes_stream().boxed()))
+    }
+
+    /// Reconnect after a connection error, unless no connection could be opened at all
+    /// or the last connections all failed
+    fn retry_after(&mut self, error: &Error) -> bool {
+        let transient = match error {
+            Error::Transport(error) => !error.is_builder(),
+            Error::Timeout(_) => true,
+            _ => false,
+        };
+        self.failures += 1;
+        transient && self.opened && self.failures <= MAX_RECONNECTS
+    }
+
+    async fn next_event<T>(mut self) -> Option<(Result<Event<T>>, Self)>
+    where
+        T: DeserializeOwned,
+    {
+        loop {
+            if let Some(event) = self.parser.next() {
+                let data = serde_json::from_str(&event.data)
+                    .map_err(|error| Error::deserialize(error, event.data.as_bytes()));
+                let event = data.map(|data| Event {
+                    id: event.id,
+                    event: event.event,
+                    data,
+                });
+                return Some((event, self));
+            }
+            if self.done {
+                return None;
+            }
+            match &mut self.body {
+                Some(body) => match body.next().await {
+                    Some(Ok(chunk)) => self.parser.push(&chunk),
+                    Some(Err(_)) | None => {
+                        self.body = None;
+                        self.parser.reset();
+                    }
+                },
+                None => match self.connect().await {
+                    Ok(Some(body)) => {
+                        self.body = Some(body);
+                        self.opened = true;
+                        self.failures = 0;
+                    }
+                    Ok(None) => self.done = true,
+                    Err(error) if self.retry_after(&error) => {}
+                    Err(error) => {
+                        self.done = true;
+                        return Some((Err(error), self));
+                    }
+                },
+            }
+        }
+    }
+}
+
+pub(crate) fn events<'a, T>(
+    client: &'a ApiClient,
+    uri: &str,
+) -> impl Stream<Item = Result<Event<T>>> + 'a
+where
+    T: DeserializeOwned + 'a,
+{
+    let source = EventSource {
+        client,
+        uri: uri.to_owned(),
+        body: None,
+        parser: Parser::default(),
+        reconnect: false,
+        opened: false,
+        failures: 0,
+        done: false,
+    };
+    stream::unfold(source, EventSource::next_event::<T>)
+}
@@ -0,0 +1,160 @@
+use crate::{Error, Result};
+use bytes::{Buf, Bytes, BytesMut};
+use futures_util::{Stream, StreamExt, TryStreamExt, stream, stream::BoxStream};
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+
+/// Where the decoder is in the body
+enum State {
+    Start,
+    Array { first: bool },
+    AfterItem,
+    Lines,
+    End,
+}
+
+/// Splits a body into json values while it is being received.
+/// A body that starts with `[` is a json array and every element is a value,
+/// any other body is a sequence of values separated by whitespace, like newline delimited json.
+struct Decoder {
+    buffer: BytesMut,
+    state: State,
+}
+
+impl Decoder {
+    fn new() -> Self {
+        Self {
+            buffer: BytesMut::new(),
+            state: State::Start,
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// The next complete value, or `None` when more of the body is needed.
+    /// With `eof` the whole body has been pushed, and `None` means there are no more values.
+    fn next<T>(&mut self, eof: bool) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        loop {
+            let skip = self
+                .buffer
+                .iter()
+                .take_while(|byte| byte.is_ascii_whitespace())
+                .count();
+            self.buffer.advance(skip);
+            let Some(&byte) = self.buffer.first() else {
+                return match self.state {
+                    State::Array { .. } | State::AfterItem if eof => {
+                        Err(invalid("unterminated json array", &self.buffer))
+                    }
+                    _ => Ok(None),
+                };
+            };
+            match (&self.state, byte) {
+                (State::Start, b'[') => {
+                    self.buffer.advance(1);
+                    self.state = State::Array { first: true };
+                }
+                (State::Start, _) => self.state = State::Lines,
+                (State::Array { first: true }, b']') | (State::AfterItem, b']') => {
+                    self.buffer.advance(1);
+                    self.state = State::End;
+                }
+                (State::AfterItem, b',') => {
+                    self.buffer.advance(1);
+                    self.state = State::Array { first: false };
+                }
+                (State::AfterItem, _) => {
+                    return Err(invalid("expected , or ] in json array", &self.buffer));
+                }
+                (State::Array { .. }, _) => {
+                    let value = self.value(eof)?;
+                    if value.is_some() {
+                        self.state = State::AfterItem;
+                    }
+                    return Ok(value);
+                }
+                (State::Lines, _) => return self.value(eof),
+                (State::End, _) => {
+                    return Err(invalid("trailing data after json array", &self.buffer));
+                }
+            }
+        }
+    }
+
+    /// Deserialize the value at the start of the buffer, if it has been received completely
+    fn value<T>(&mut self, eof: bool) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let mut values = serde_json::Deserializer::from_slice(&self.buffer).into_iter::<T>();
+        match values.next() {
+            Some(Ok(value)) => {
+                let end = values.byte_offset();
+                // a number or literal at the end of the buffer may continue in the next chunk
+                let delimited = matches!(self.buffer[end - 1], b'}' | b']' | b'"');
+                if delimited || end < self.buffer.len() || eof {
+                    self.buffer.advance(end);
+                    Ok(Some(value))
+                } else {
+                    Ok(None)
+                }
+            }
+            Some(Err(error)) if error.is_eof() && !eof => Ok(None),
+            Some(Err(error)) => Err(Error::deserialize(error, &self.buffer)),
+            None => Ok(None),
+        }
+    }
+}
+
+fn invalid(message: &str, payload: &[u8]) -> Error {
+    Error::deserialize(serde::de::Error::custom(message), payload)
+}
+
+/// The body of a response that is being received, with the values decoded so far
+struct Items {
+    body: BoxStream<'static, reqwest::Result<Bytes>>,
+    decoder: Decoder,
+    eof: bool,
+}
+
+impl Items {
+    async fn next_item<T>(mut self) -> Result<Option<(T, Self)>>
+    where
+        T: DeserializeOwned,
+    {
+        loop {
+            if let Some(item) = self.decoder.next(self.eof)? {
+                return Ok(Some((item, self)));
+            }
+            if self.eof {
+                return Ok(None);
+            }
+            match self.body.next().await {
+                Some(chunk) => self.decoder.push(&chunk?),
+                None => self.eof = true,
+            }
+        }
+    }
+}
+
+pub(crate) fn items<T, F>(response: F) -> impl Stream<Item = Result<T>>
+where
+    T: DeserializeOwned,
+    F: Future<Output = Result<Response>>,
+{
+    stream::once(response)
+        .map_ok(|response| {
+            let items = Items {
+                body: response.bytes_stream().boxed(),
+                decoder: Decoder::new(),
+                eof: false,
+            };
+            stream::try_unfold(items, Items::next_item::<T>)
+        })
+        .try_flatten()
+}
@@ -1,122 +1,382 @@
 #![doc = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/README.md"))]
 
-use futures_util::{TryFutureExt, future::ready};
-pub use reqwest::Error;
-use reqwest::{Client, RequestBuilder, Response, Result};
+pub use authentication::{ApiKeyLocation, Authentication, Authenticator, BasicAuthentication};
+pub use cache::{CacheStore, CachedResponse, DiskStore, MemoryStore, ResponseCache};
+use context::RequestIdHeader;
+pub use context::{TraceContext, TraceParent};
+pub use download::{Download, Progress};
+pub use error::{Error, Result, StatusError};
+pub use events::Event;
+use futures_util::{Stream, TryFutureExt};
+use meter::RequestMeter;
+pub use middleware::{Middleware, Next};
+pub use multipart::Multipart;
+pub use oauth2::{OAuth2Config, OAuth2TokenProvider};
+pub use paginate::Pagination;
+pub use path::path_segments;
+pub use problem::ProblemDetails;
+pub use request::ApiRequestBuilder;
+use reqwest::{
+    Client, RequestBuilder, Url,
+    header::{CONTENT_TYPE, HeaderName, HeaderValue},
+};
+pub use reqwest::{Method, Request, Response, StatusCode, header};
+pub use response::ResponseHead;
+pub use retry::RetryPolicy;
+pub use secret::Secret;
 use serde::{Serialize, de::DeserializeOwned};
+pub use signing::{Canonicalization, HmacSigner, SigningInput};
+use std::{sync::Arc, time::Duration};
+pub use token::{SignedTokenProvider, TokenProvider};
+use tokio::io::AsyncWrite;
+use trace::RequestTrace;
 
+mod authentication;
+mod cache;
+mod context;
+mod credentials;
+mod download;
+mod error;
+mod events;
+mod json_stream;
+mod meter;
+mod middleware;
+mod multipart;
+mod oauth2;
+mod paginate;
+mod path;
+mod problem;
+mod request;
+mod response;
+mod retry;
+mod secret;
+mod signing;
+mod token;
+mod trace;
+
+const MERGE_PATCH_JSON: HeaderValue = HeaderValue::from_static("application/merge-patch+json");
+const JSON_PATCH_JSON: HeaderValue = HeaderValue::from_static("application/json-patch+json");
 const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+const UNKNOWN_ROUTE: &str = "unknown";
 
 /// Before one can do any api request, an ApiClient must be constructed
 pub struct ApiClient {
     client: Client,
-    prefix: String,
-    authentication: Authentication,
+    prefix: Url,
+    authentication: Arc<dyn Authenticator>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    sensitive_headers: Arc<[HeaderName]>,
+    trace_context: Option<TraceContext>,
+    request_id_header: Option<HeaderName>,
+    error_for_status: bool,
+    retry_policy: Option<RetryPolicy>,
 }
 
 pub struct ApiClientBuilder {
     prefix: String,
-    authentication: Authentication,
+    authentication: Arc<dyn Authenticator>,
+    discover_authentication: Option<String>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    sensitive_headers: Vec<HeaderName>,
+    trace_context: Option<TraceContext>,
+    request_id_header: Option<HeaderName>,
     user_agent: Option<String>,
+    error_for_status: bool,
+    retry_policy: Option<RetryPolicy>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
 }
 
 impl ApiClientBuilder {
     pub fn new(prefix: &str) -> Self {
         Self {
             prefix: prefix.to_owned(),
-            authentication: Authentication::default(),
+            authentication: Arc::new(Authentication::default()),
+            discover_authentication: None,
+            middleware: vec![],
+            sensitive_headers: vec![],
+            trace_context: None,
+            request_id_header: None,
             user_agent: None,
+            error_for_status: true,
+            retry_policy: None,
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
         }
     }
-    pub fn authentication(&mut self, auth: Authentication) -> &mut Self {
-        self.authentication = auth;
+    /// Authenticate requests with one of the [`Authentication`] schemes or a custom [`Authenticator`]
+    pub fn authentication<A>(&mut self, auth: A) -> &mut Self
+    where
+        A: Authenticator + 'static,
+    {
+        self.authentication = Arc::new(auth);
+        self.discover_authentication = None;
+        self
+    }
+    /// Find credentials when the client is built, looking in the environment variables starting
+    /// with `env_prefix`, then in the netrc file for the host of the prefix. See [`Authentication::discover`] for the order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rest_json_client::{ApiClientBuilder, Error};
+    /// #
+    /// // uses LIPL_TOKEN, LIPL_USERNAME and LIPL_PASSWORD, LIPL_CREDENTIALS_FILE or ~/.netrc
+    /// let client = ApiClientBuilder::new("https://www.paulmin.nl/lipl/api/v1/")
+    ///     .discover_authentication("LIPL")
+    ///     .build()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn discover_authentication(&mut self, env_prefix: &str) -> &mut Self {
+        self.discover_authentication = Some(env_prefix.to_owned());
+        self
+    }
+    /// Add a layer to the middleware stack. Layers run in the order they are added.
+    pub fn middleware<M>(&mut self, middleware: M) -> &mut Self
+    where
+        M: Middleware + 'static,
+    {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+    /// Redact the value of this header in traces, in addition to credentials and cookies.
+    /// Only has effect with the `tracing` feature.
+    pub fn sensitive_header(&mut self, name: HeaderName) -> &mut Self {
+        self.sensitive_headers.push(name);
+        self
+    }
+    /// Send a W3C `traceparent` and `tracestate` header with every call
+    pub fn trace_context(&mut self, trace_context: TraceContext) -> &mut Self {
+        self.trace_context = Some(trace_context);
+        self
+    }
+    /// Send a generated request id in the header `name`, for example `X-Request-Id`, with every call.
+    /// The id echoed by the server is available with [`StatusError::request_id`].
+    pub fn request_id_header(&mut self, name: HeaderName) -> &mut Self {
+        self.request_id_header = Some(name);
         self
     }
     pub fn user_agent(&mut self, user_agent: &str) -> &mut Self {
         self.user_agent = Some(user_agent.to_owned());
         self
     }
+    /// By default a response with a non success status code is turned into [`Error::Status`].
+    /// Disable this for api's that return meaningful json in non success responses.
+    /// This only applies to calls that deserialize json, like [`ApiClient::get`] and [`ApiRequestBuilder::send_stream`].
+    pub fn error_for_status(&mut self, error_for_status: bool) -> &mut Self {
+        self.error_for_status = error_for_status;
+        self
+    }
+    /// Retry failed requests according to the given policy. Without a policy every call makes exactly one attempt.
+    pub fn retry_policy(&mut self, retry_policy: RetryPolicy) -> &mut Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+    /// Maximum time allowed to establish a connection with the server
+    pub fn connect_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    /// Maximum time allowed between two reads of the response
+    pub fn read_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+    /// Maximum time allowed for a complete request, from connecting until the response body is read.
+    /// An elapsed timeout results in [`Error::Timeout`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rest_json_client::{ApiClientBuilder, Error};
+    /// # use std::time::Duration;
+    /// #
+    /// let client = ApiClientBuilder::new("https://jsonplaceholder.typicode.com/")
+    ///     .connect_timeout(Duration::from_secs(2))
+    ///     .timeout(Duration::from_secs(10))
+    ///     .build()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// Create the client. Fails with [`Error::Url`] if the prefix is not a valid absolute url
+    /// and with [`Error::Auth`] if credentials are to be discovered but none are found.
     pub fn build(&self) -> Result<ApiClient> {
-        Client::builder()
-            .user_agent(
-                self.user_agent
-                    .clone()
-                    .unwrap_or(DEFAULT_USER_AGENT.to_owned()),
-            )
+        let prefix = path::parse_prefix(&self.prefix)?;
+        let authentication = match &self.discover_authentication {
+            Some(env_prefix) => Arc::new(Authentication::discover(
+                env_prefix,
+                prefix.host_str().unwrap_or_default(),
+            )?),
+            None => self.authentication.clone(),
+        };
+        let mut builder = Client::builder().user_agent(
+            self.user_agent
+                .clone()
+                .unwrap_or(DEFAULT_USER_AGENT.to_owned()),
+        );
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder
             .build()
+            .map_err(Error::from)
             .map(|client| ApiClient {
-                authentication: self.authentication.clone(),
+                authentication,
+                middleware: self.middleware.clone(),
+                sensitive_headers: self.sensitive_headers.clone().into(),
+                trace_context: self.trace_context.clone(),
+                request_id_header: self.request_id_header.clone(),
                 client,
-                prefix: self.prefix.clone(),
+                prefix,
+                error_for_status: self.error_for_status,
+                retry_policy: self.retry_policy.clone(),
             })
     }
 }
-/// This library support two ways of authentication
-/// Either Basic of Bearer
-#[derive(Clone, Default)]
-pub enum Authentication {
-    Basic(BasicAuthentication),
-    Bearer(Option<String>),
-    #[default]
-    None,
-}
 
-impl Authentication {
-    pub fn new_basic(username: &str, password: &str) -> Self {
-        Authentication::Basic(BasicAuthentication::new(username, password))
-    }
-    pub fn new_bearer(token: &str) -> Self {
-        Authentication::Bearer(Some(token.to_owned()))
+impl ApiClient {
+    fn create_request(&self, method: Method, uri: &str) -> Result<RequestBuilder> {
+        Ok(self.client.request(method, self.uri(uri)?))
     }
-}
 
-#[derive(Clone)]
-pub struct BasicAuthentication {
-    username: String,
-    password: String,
-}
+    /// Start building a request with the given method for the given uri.
+    /// Use this when the request needs a query string, extra headers or a timeout of its own.
+    pub fn request(&self, method: Method, uri: &str) -> ApiRequestBuilder<'_> {
+        let idempotent = method.is_idempotent();
+        ApiRequestBuilder::new(self, self.create_request(method, uri), idempotent)
+    }
 
-impl BasicAuthentication {
-    /// Create a new instance of BasicAuthentication with provided username and password
-    pub fn new<S: Into<String>>(username: S, password: S) -> Self {
-        Self {
-            username: username.into(),
-            password: password.into(),
-        }
+    pub(crate) async fn send(
+        &self,
+        builder: RequestBuilder,
+        idempotent: bool,
+        route: Option<&str>,
+    ) -> Result<Response> {
+        self.send_with(builder, idempotent, route, |response| async {
+            Ok(response)
+        })
+        .await
     }
-}
 
-impl ApiClient {
-    fn create_request<T>(
+    /// Send the request and read the response with `read`.
+    /// Metrics record the outcome of `read`, so a body that cannot be read is counted as an error.
+    pub(crate) async fn send_with<T, F, Fut>(
         &self,
-        uri: &str,
-        f: impl Fn(&Client, String) -> RequestBuilder,
-        t: Option<T>,
-    ) -> RequestBuilder
+        builder: RequestBuilder,
+        idempotent: bool,
+        route: Option<&str>,
+        read: F,
+    ) -> Result<T>
     where
-        T: Serialize,
+        F: FnOnce(Response) -> Fut,
+        Fut: Future<Output = Result<T>>,
     {
-        let mut builder = f(&self.client, self.uri(uri));
-        builder = match &self.authentication {
-            Authentication::Basic(basic) => {
-                builder.basic_auth(basic.username.clone(), Some(basic.password.clone()))
-            }
-            Authentication::Bearer(token) => match token {
-                Some(t) => builder.bearer_auth(t.clone()),
-                None => builder,
-            },
-            Authentication::None => builder,
+        let mut request = builder.build()?;
+        if let Some(trace_context) = &self.trace_context {
+            trace_context.inject(&mut request);
+        }
+        if let Some(name) = &self.request_id_header {
+            context::inject_request_id(&mut request, name);
+        }
+        // a path would give every id its own label, requests without a route template share one
+        let route = route.map_or(UNKNOWN_ROUTE, |route| route.trim_start_matches('/'));
+        let trace = RequestTrace::new(&request, route, &self.sensitive_headers);
+        let meter = RequestMeter::new(&request, route);
+        let mut result = trace
+            .instrument(self.execute(request, idempotent, &trace, &meter))
+            .await;
+        trace.finish(&result);
+        if let (Ok(response), Some(name)) = (&mut result, &self.request_id_header) {
+            response
+                .extensions_mut()
+                .insert(RequestIdHeader(name.clone()));
+        }
+        let (status, result) = match result {
+            Ok(response) => (Some(response.status()), read(response).await),
+            Err(error) => (None, Err(error)),
         };
+        meter.finish(status, &result);
+        result
+    }
 
-        if let Some(object) = t {
-            builder = builder.json(&object)
+    /// Authenticate and send the request, repeating it according to the retry policy
+    /// and once with fresh credentials after an unauthorized response
+    async fn execute(
+        &self,
+        mut request: Request,
+        idempotent: bool,
+        trace: &RequestTrace,
+        meter: &RequestMeter,
+    ) -> Result<Response> {
+        let policy = self
+            .retry_policy
+            .as_ref()
+            .filter(|policy| idempotent || policy.retries_post());
+        let max_attempts = policy.map(RetryPolicy::max_attempts).unwrap_or(1);
+        let mut attempt = 1;
+        let mut reauthenticated = false;
+        loop {
+            let next = request.try_clone();
+            self.authentication.authenticate(&mut request).await?;
+            trace.attempt(attempt, &request);
+            meter.attempt(attempt);
+            let signed = self.authentication.signs_every_request();
+            let result = Next::new(&self.client, &self.middleware, signed)
+                .run(request)
+                .await;
+            let Some(next) = next else {
+                return result;
+            };
+            match (&result, policy) {
+                (Ok(response), _)
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        && !reauthenticated
+                        && self.authentication.invalidate() =>
+                {
+                    reauthenticated = true;
+                }
+                (Ok(response), Some(policy))
+                    if attempt < max_attempts && retry::is_retryable_response(response) =>
+                {
+                    let Some(delay) = policy.delay(attempt, Some(response.headers())) else {
+                        return result;
+                    };
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                (Err(error), Some(policy))
+                    if attempt < max_attempts && retry::is_retryable_error(error) =>
+                {
+                    tokio::time::sleep(policy.delay(attempt, None).unwrap_or_default()).await;
+                    attempt += 1;
+                }
+                _ => return result,
+            }
+            request = next;
         }
+    }
 
-        builder
+    pub(crate) async fn check_status(&self, response: Response) -> Result<Response> {
+        if self.error_for_status {
+            error_for_status(response).await
+        } else {
+            Ok(response)
+        }
     }
 
-    fn uri(&self, uri: &str) -> String {
-        format!("{}{}", self.prefix, uri)
+    fn uri(&self, uri: &str) -> Result<Url> {
+        path::join(&self.prefix, uri)
     }
 
     /// # Example
@@ -138,10 +398,7 @@ impl ApiClient {
     /// # });
     /// ```
     pub async fn delete(&self, uri: &str) -> Result<()> {
-        self.create_request::<()>(uri, Client::delete, None)
-            .send()
-            .and_then(|r| ready(r.error_for_status()).map_ok(|_| ()))
-            .await
+        self.request(Method::DELETE, uri).send_empty().await
     }
 
     /// # Example 1
@@ -183,14 +440,119 @@ impl ApiClient {
     /// #       Ok::<(), Error>(())
     /// # });
     /// ```
+    ///
+    /// # Example 3
+    ///
+    /// Try to return a post that does not exist on [Json Placeholder](https://jsonplaceholder.typicode.com/)
+    ///
+    /// ```rust
+    /// # use rest_json_client::{ApiClientBuilder, Authentication, Error};
+    /// # use json_placeholder_data::posts::Post;
+    /// # use rest_json_client::StatusCode;
+    /// #
+    /// # tokio_test::block_on(async {
+    ///     let base = "https://jsonplaceholder.typicode.com/";
+    ///     let result = ApiClientBuilder::new(base)
+    ///         .build()?
+    ///         .get::<Post>("posts/0")
+    ///         .await;
+    ///
+    ///     let not_found = match result {
+    ///         Err(Error::Status(error)) => error.status() == StatusCode::NOT_FOUND,
+    ///         other => other.map(|_| false)?,
+    ///     };
+    ///
+    /// #       assert!(not_found);
+    /// #       Ok::<(), Error>(())
+    /// # });
+    /// ```
     pub async fn get<R>(&self, uri: &str) -> Result<R>
     where
         R: DeserializeOwned,
     {
-        self.create_request::<()>(uri, Client::get, None)
-            .send()
-            .and_then(Response::json::<R>)
-            .await
+        self.request(Method::GET, uri).send_json().await
+    }
+
+    /// Return the items of a json array, or of a newline delimited json body, one by one as they are received.
+    /// Use this instead of [`ApiClient::get`] for large collections, so that the collection is never in memory as a whole.
+    ///
+    /// # Example
+    ///
+    /// Count the photos of [Json Placeholder](https://jsonplaceholder.typicode.com/) in the first album
+    ///
+    /// ```
+    /// # use rest_json_client::{ApiClientBuilder, Error};
+    /// # use json_placeholder_data::photos::Photo;
+    /// # use futures_util::TryStreamExt;
+    /// #
+    /// # tokio_test::block_on(async {
+    ///     let base = "https://jsonplaceholder.typicode.com/";
+    ///     let client = ApiClientBuilder::new(base).build()?;
+    ///     let count = client
+    ///         .get_stream::<Photo>("photos")
+    ///         .try_filter(|photo| std::future::ready(photo.album_id == 1))
+    ///         .try_fold(0, |count, _| async move { Ok(count + 1) })
+    ///         .await?;
+    ///
+    /// #     assert_eq!(count, 50);
+    /// #     Ok::<(), Error>(())
+    /// # });
+    /// ```
+    pub fn get_stream<'a, R>(&'a self, uri: &str) -> impl Stream<Item = Result<R>> + 'a
+    where
+        R: DeserializeOwned + 'a,
+    {
+        self.request(Method::GET, uri).send_stream()
+    }
+
+    /// Subscribe to the [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html) at the uri,
+    /// deserializing the data of every event as json.
+    ///
+    /// When the connection is closed or lost, the client reconnects after the delay sent by the server in a `retry` field,
+    /// three seconds by default, and sends the id of the last event in the `Last-Event-ID` header so that the server can
+    /// continue where it left off. Every connection is authenticated like any other request. The stream ends when the server
+    /// responds with `204 No Content`, with an error status or with a content type other than `text/event-stream`.
+    /// A connection error ends the stream when the first connection cannot be opened, or when three reconnects in a row fail.
+    /// Data that cannot be deserialized is returned as an error, without ending the stream.
+    ///
+    /// Do not set an overall [timeout](ApiClientBuilder::timeout) on a client that is used for events,
+    /// as it would close the connection after the timeout.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rest_json_client::{ApiClientBuilder, Error};
+    /// # use futures_util::TryStreamExt;
+    /// # use serde_json::Value;
+    /// #
+    /// # tokio_test::block_on(async {
+    ///     let base = "https://www.paulmin.nl/lipl/api/v1/";
+    ///     let client = ApiClientBuilder::new(base).build()?;
+    ///     let mut events = std::pin::pin!(client.events::<Value>("events"));
+    ///     while let Some(event) = events.try_next().await? {
+    ///         println!("{} {}: {}", event.event, event.id.unwrap_or_default(), event.data);
+    ///     }
+    /// #     Ok::<(), Error>(())
+    /// # });
+    /// ```
+    pub fn events<'a, R>(&'a self, uri: &str) -> impl Stream<Item = Result<Event<R>>> + 'a
+    where
+        R: DeserializeOwned + 'a,
+    {
+        events::events(self, uri)
+    }
+
+    /// Return the items of a collection that is spread over pages, requesting the next page
+    /// when the items of the previous page are consumed. See [`Pagination`] for an example.
+    pub fn paginate<'a, T>(
+        &'a self,
+        uri: &str,
+        pagination: Pagination,
+    ) -> impl Stream<Item = Result<T>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        paginate::paginate(self, self.uri(uri), pagination)
     }
 
     /// # Example
@@ -225,9 +587,21 @@ impl ApiClient {
         T: Serialize,
         R: DeserializeOwned,
     {
-        self.create_request::<T>(uri, Client::post, Some(object))
-            .send()
-            .and_then(Response::json::<R>)
+        self.request(Method::POST, uri)
+            .json(&object)
+            .send_json()
+            .await
+    }
+
+    /// Post a `multipart/form-data` body, like files with the fields that describe them, and deserialize the json response.
+    /// See [`Multipart`] for an example.
+    pub async fn post_multipart<R>(&self, uri: &str, form: Multipart) -> Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        self.request(Method::POST, uri)
+            .multipart(form)
+            .send_json()
             .await
     }
 
@@ -237,12 +611,13 @@ impl ApiClient {
         T: Serialize,
     {
         let token = self
-            .create_request::<T>(uri, Client::post, Some(object))
+            .request(Method::POST, uri)
             .header("Signature", signature)
-            .send()
-            .and_then(Response::text)
+            .json(&object)
+            .send_text()
+            .map_err(token::rejected)
             .await?;
-        self.authentication = Authentication::Bearer(Some(token));
+        self.authentication = Arc::new(Authentication::Bearer(Some(Secret::new(token))));
         Ok(())
     }
 
@@ -278,9 +653,145 @@ impl ApiClient {
         T: Serialize,
         R: DeserializeOwned,
     {
-        self.create_request::<T>(uri, Client::put, Some(object))
-            .send()
-            .and_then(Response::json::<R>)
+        self.request(Method::PUT, uri)
+            .json(&object)
+            .send_json()
             .await
     }
+
+    /// Partially update a resource with a json body and return the deserialized response
+    ///
+    /// # Example
+    ///
+    /// Try to change the title of a post with specific id on [Json Placeholder](https://jsonplaceholder.typicode.com/)
+    ///
+    /// ```
+    /// # use rest_json_client::{ApiClientBuilder, Error};
+    /// # use json_placeholder_data::posts::Post;
+    /// # use std::collections::HashMap;
+    /// #
+    /// # tokio_test::block_on(async {
+    ///     let base = "https://jsonplaceholder.typicode.com/";
+    ///     let post = ApiClientBuilder::new(base)
+    ///         .build()?
+    ///         .patch::<_, Post>("posts/1", HashMap::from([("title", "Hallo")]))
+    ///         .await?;
+    ///
+    /// #     assert_eq!(post.title, "Hallo");
+    /// #     Ok::<(), Error>(())
+    /// # });
+    /// ```
+    pub async fn patch<T, R>(&self, uri: &str, object: T) -> Result<R>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        self.request(Method::PATCH, uri)
+            .json(&object)
+            .send_json()
+            .await
+    }
+
+    /// Partially update a resource with a [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7396) document
+    pub async fn merge_patch<T, R>(&self, uri: &str, object: T) -> Result<R>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        self.request(Method::PATCH, uri)
+            .header(CONTENT_TYPE, MERGE_PATCH_JSON)
+            .json(&object)
+            .send_json()
+            .await
+    }
+
+    /// Partially update a resource with a [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902) document
+    pub async fn json_patch<T, R>(&self, uri: &str, operations: T) -> Result<R>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        self.request(Method::PATCH, uri)
+            .header(CONTENT_TYPE, JSON_PATCH_JSON)
+            .json(&operations)
+            .send_json()
+            .await
+    }
+
+    /// Write the body of the resource to `writer` while it is received, returning the number of bytes written.
+    /// An interrupted download is resumed, see [`Download`] for the options and an example.
+    pub async fn download<W>(&self, uri: &str, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        self.download_with(uri, writer, Download::new()).await
+    }
+
+    /// Write the body of the resource to `writer` like [`ApiClient::download`], with progress reporting
+    /// or continuing a download that was written partially before
+    pub async fn download_with<W>(
+        &self,
+        uri: &str,
+        writer: &mut W,
+        download: Download,
+    ) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        download::download(self, uri, writer, download).await
+    }
+
+    /// Return status and headers for a resource, without transferring the body.
+    /// Useful to check if a resource exists.
+    ///
+    /// # Example
+    ///
+    /// Check if a post exists on [Json Placeholder](https://jsonplaceholder.typicode.com/)
+    ///
+    /// ```
+    /// # use rest_json_client::{ApiClientBuilder, Error};
+    /// #
+    /// # tokio_test::block_on(async {
+    ///     let base = "https://jsonplaceholder.typicode.com/";
+    ///     let head = ApiClientBuilder::new(base)
+    ///         .build()?
+    ///         .head("posts/1")
+    ///         .await?;
+    ///
+    /// #     assert!(head.status().is_success());
+    /// #     Ok::<(), Error>(())
+    /// # });
+    /// ```
+    pub async fn head(&self, uri: &str) -> Result<ResponseHead> {
+        self.request(Method::HEAD, uri).send_head().await
+    }
+
+    /// Return status and headers of an options request, [`ResponseHead::allow`] lists the supported methods
+    pub async fn options(&self, uri: &str) -> Result<ResponseHead> {
+        self.request(Method::OPTIONS, uri).send_head().await
+    }
+}
+
+async fn error_for_status(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        let request_id_header = response
+            .extensions()
+            .get::<RequestIdHeader>()
+            .map(|header| header.0.clone())
+            .unwrap_or(context::X_REQUEST_ID);
+        let headers = response.headers().clone();
+        let body = response.text().await.unwrap_or_default();
+        Err(StatusError::new(status, headers, body, &request_id_header).into())
+    } else {
+        Ok(response)
+    }
+}
+
+async fn json<R>(response: Response) -> Result<R>
+where
+    R: DeserializeOwned,
+{
+    let bytes = response.bytes().await?;
+    serde_json::from_slice(&bytes).map_err(|error| Error::deserialize(error, &bytes))
 }
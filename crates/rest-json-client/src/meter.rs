@@ -0,0 +1,85 @@
+use crate::Result;
+use reqwest::{Request, StatusCode};
+
+#[cfg(feature = "metrics")]
+pub(crate) use enabled::RequestMeter;
+
+/// Metrics are only recorded with the `metrics` feature
+#[cfg(not(feature = "metrics"))]
+pub(crate) struct RequestMeter;
+
+#[cfg(not(feature = "metrics"))]
+impl RequestMeter {
+    pub(crate) fn new(_: &Request, _: &str) -> Self {
+        Self
+    }
+
+    pub(crate) fn attempt(&self, _: u32) {}
+
+    pub(crate) fn finish<T>(&self, _: Option<StatusCode>, _: &Result<T>) {}
+}
+
+#[cfg(feature = "metrics")]
+mod enabled {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        time::Instant,
+    };
+
+    const REQUESTS: &str = "http_client_requests_total";
+    const DURATION: &str = "http_client_request_duration_seconds";
+    const RETRIES: &str = "http_client_retries_total";
+
+    /// Records the outcome of all attempts of one request, including reading the response body.
+    /// A request without a response has status class `none`, a successful request has error kind `none`.
+    /// Streamed bodies are not awaited, those requests are counted when the response arrives.
+    pub(crate) struct RequestMeter {
+        method: String,
+        route: String,
+        start: Instant,
+        attempts: AtomicU32,
+    }
+
+    impl RequestMeter {
+        pub(crate) fn new(request: &Request, route: &str) -> Self {
+            Self {
+                method: request.method().to_string(),
+                route: route.to_owned(),
+                start: Instant::now(),
+                attempts: AtomicU32::new(0),
+            }
+        }
+
+        pub(crate) fn attempt(&self, attempt: u32) {
+            self.attempts.store(attempt, Ordering::Relaxed);
+        }
+
+        pub(crate) fn finish<T>(&self, status: Option<StatusCode>, result: &Result<T>) {
+            let status_class = status.map_or("none", |status| status_class(status.as_u16()));
+            let error_kind = result.as_ref().err().map_or("none", |error| error.kind());
+            let labels = [
+                ("method", self.method.clone()),
+                ("route", self.route.clone()),
+                ("status_class", status_class.to_owned()),
+                ("error_kind", error_kind.to_owned()),
+            ];
+            metrics::counter!(REQUESTS, &labels).increment(1);
+            metrics::histogram!(DURATION, &labels).record(self.start.elapsed().as_secs_f64());
+            let retries = self.attempts.load(Ordering::Relaxed).saturating_sub(1);
+            if retries > 0 {
+                metrics::counter!(RETRIES, &labels[..2]).increment(retries.into());
+            }
+        }
+    }
+
+    fn status_class(status: u16) -> &'static str {
+        match status {
+            100..=199 => "1xx",
+            200..=299 => "2xx",
+            300..=399 => "3xx",
+            400..=499 => "4xx",
+            _ => "5xx",
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{Error, Result};
+use futures_util::{FutureExt, TryFutureExt, future::BoxFuture};
+use reqwest::{Client, Request, Response};
+use std::sync::Arc;
+
+/// A layer around every attempt to send a request, configured with
+/// [`ApiClientBuilder::middleware`](crate::ApiClientBuilder::middleware)
+///
+/// Layers run in the order they were added, after the request is authenticated.
+/// A layer can modify the request before passing it on with [`Next::run`],
+/// observe or replace the response or error, or return a response without calling
+/// the next layer at all. A response can be created from an [`http::Response`] with `Response::from`.
+///
+/// # Example
+///
+/// ```
+/// # use futures_util::{FutureExt, future::BoxFuture};
+/// # use rest_json_client::{ApiClientBuilder, Error, Middleware, Next, Request, Response, Result};
+/// #
+/// struct Log;
+///
+/// impl Middleware for Log {
+///     fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+///         async move {
+///             let method = request.method().clone();
+///             let url = request.url().clone();
+///             let result = next.run(request).await;
+///             match &result {
+///                 Ok(response) => eprintln!("{method} {url}: {}", response.status()),
+///                 Err(error) => eprintln!("{method} {url}: {error}"),
+///             }
+///             result
+///         }
+///         .boxed()
+///     }
+/// }
+///
+/// let client = ApiClientBuilder::new("https://jsonplaceholder.typicode.com/")
+///     .middleware(Log)
+///     .build()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub trait Middleware: Send + Sync {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>>;
+}
+
+impl<M> Middleware for Arc<M>
+where
+    M: Middleware + ?Sized,
+{
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        self.as_ref().handle(request, next)
+    }
+}
+
+/// The remaining layers of the middleware stack, ending with sending the request
+pub struct Next<'a> {
+    client: &'a Client,
+    middleware: &'a [Arc<dyn Middleware>],
+    signed: bool,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        client: &'a Client,
+        middleware: &'a [Arc<dyn Middleware>],
+        signed: bool,
+    ) -> Self {
+        Self {
+            client,
+            middleware,
+            signed,
+        }
+    }
+
+    /// The request carries credentials that differ for every request, see [`Authenticator::signs_every_request`](crate::Authenticator::signs_every_request)
+    pub(crate) fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// Pass the request to the next layer, or send it if this is the last layer
+    pub fn run(self, request: Request) -> BoxFuture<'a, Result<Response>> {
+        match self.middleware.split_first() {
+            Some((layer, middleware)) => layer.handle(
+                request,
+                Next {
+                    client: self.client,
+                    middleware,
+                    signed: self.signed,
+                },
+            ),
+            None => self.client.execute(request).map_err(Error::from).boxed(),
+        }
+    }
+}
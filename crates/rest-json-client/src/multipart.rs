@@ -0,0 +1,117 @@
+use crate::{Error, Result};
+use futures_util::TryStream;
+use reqwest::{
+    Body,
+    multipart::{Form, Part},
+};
+use serde::Serialize;
+use std::{borrow::Cow, path::Path};
+
+const APPLICATION_JSON: &str = "application/json";
+
+/// A `multipart/form-data` body, sent with [`ApiClient::post_multipart`](crate::ApiClient::post_multipart)
+/// or [`ApiRequestBuilder::multipart`](crate::ApiRequestBuilder::multipart)
+///
+/// A part that cannot be created, because a file cannot be opened or a content type is invalid,
+/// is reported when the request is sent.
+///
+/// # Example
+///
+/// ```no_run
+/// # use rest_json_client::{ApiClientBuilder, Error, Multipart};
+/// # use serde_json::{Value, json};
+/// #
+/// # tokio_test::block_on(async {
+///     let form = Multipart::new()
+///         .text("title", "Roodkapje")
+///         .json("lyric", &json!({"parts": [["Daar was laatst een meisje loos"]]}))
+///         .file("sheet", "roodkapje.pdf", "application/pdf");
+///     let base = "https://www.paulmin.nl/lipl/api/v1/";
+///     let created = ApiClientBuilder::new(base)
+///         .build()?
+///         .post_multipart::<Value>("attachment", form)
+///         .await?;
+/// #     Ok::<(), Error>(())
+/// # });
+/// ```
+pub struct Multipart {
+    form: Result<Form>,
+}
+
+impl Default for Multipart {
+    fn default() -> Self {
+        Self {
+            form: Ok(Form::new()),
+        }
+    }
+}
+
+impl Multipart {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn part(mut self, name: &str, part: impl FnOnce() -> Result<Part>) -> Self {
+        let name = name.to_owned();
+        self.form = self.form.and_then(|form| Ok(form.part(name, part()?)));
+        self
+    }
+
+    /// Add a text field
+    pub fn text(self, name: &str, value: &str) -> Self {
+        let value = value.to_owned();
+        self.part(name, || Ok(Part::text(value)))
+    }
+
+    /// Add the serialized value as a part with content type `application/json`
+    pub fn json<T>(self, name: &str, value: &T) -> Self
+    where
+        T: Serialize + ?Sized,
+    {
+        let json = serde_json::to_vec(value).map_err(Error::Serialize);
+        self.part(name, || Ok(Part::bytes(json?).mime_str(APPLICATION_JSON)?))
+    }
+
+    /// Add bytes as a file with the given name and content type
+    pub fn bytes<B>(self, name: &str, bytes: B, file_name: &str, content_type: &str) -> Self
+    where
+        B: Into<Cow<'static, [u8]>>,
+    {
+        let part = Part::bytes(bytes).file_name(file_name.to_owned());
+        self.part(name, || Ok(part.mime_str(content_type)?))
+    }
+
+    /// Add the file at `path` with the given content type, named after the last component of the path.
+    /// The file is opened right away and read while the request is sent.
+    pub fn file<P>(self, name: &str, path: P, content_type: &str) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        self.part(name, || {
+            let file = std::fs::File::open(path)?;
+            let length = file.metadata()?.len();
+            let part = Part::stream_with_length(tokio::fs::File::from_std(file), length);
+            let part = match path.file_name() {
+                Some(file_name) => part.file_name(file_name.to_string_lossy().into_owned()),
+                None => part,
+            };
+            Ok(part.mime_str(content_type)?)
+        })
+    }
+
+    /// Add a stream of bytes as a file with the given name and content type, read while the request is sent
+    pub fn stream<S>(self, name: &str, stream: S, file_name: &str, content_type: &str) -> Self
+    where
+        S: TryStream + Send + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        bytes::Bytes: From<S::Ok>,
+    {
+        let part = Part::stream(Body::wrap_stream(stream)).file_name(file_name.to_owned());
+        self.part(name, || Ok(part.mime_str(content_type)?))
+    }
+
+    pub(crate) fn into_form(self) -> Result<Form> {
+        self.form
+    }
+}
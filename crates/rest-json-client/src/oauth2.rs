@@ -0,0 +1,242 @@
+use crate::{
+    Authenticator, Error, Result, Secret, TokenProvider,
+    authentication::authenticate_with_token,
+    token::{DEFAULT_TIMEOUT, TokenCache, token_client},
+};
+use futures_util::{FutureExt, future::BoxFuture};
+use reqwest::{Client, Request, StatusCode, Url};
+use serde::{Deserialize, Deserializer};
+use std::{
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+/// Client registration at an OAuth2 authorization server
+///
+/// # Example
+///
+/// ```
+/// # use rest_json_client::{ApiClientBuilder, Authentication, Error, OAuth2Config};
+/// #
+/// let config = OAuth2Config::new("https://auth.example.com/oauth2/token", "lipl")
+///     .client_secret("secret")
+///     .scope("lyrics:read");
+/// let client = ApiClientBuilder::new("https://www.paulmin.nl/lipl/api/v1/")
+///     .authentication(Authentication::new_client_credentials(config)?)
+///     .build()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct OAuth2Config {
+    token_url: String,
+    client_id: String,
+    client_secret: Option<Secret>,
+    scopes: Vec<String>,
+}
+
+impl OAuth2Config {
+    pub fn new(token_url: &str, client_id: &str) -> Self {
+        Self {
+            token_url: token_url.to_owned(),
+            client_id: client_id.to_owned(),
+            client_secret: None,
+            scopes: vec![],
+        }
+    }
+
+    /// Authenticate the client at the token endpoint with Basic authentication
+    pub fn client_secret(mut self, client_secret: &str) -> Self {
+        self.client_secret = Some(Secret::new(client_secret));
+        self
+    }
+
+    /// Request an additional scope
+    pub fn scope(mut self, scope: &str) -> Self {
+        self.scopes.push(scope.to_owned());
+        self
+    }
+}
+
+enum Grant {
+    ClientCredentials,
+    RefreshToken(Mutex<Secret>),
+}
+
+/// Obtains access tokens from an OAuth2 token endpoint with the client credentials or refresh token grant
+///
+/// Tokens are cached and refreshed before they expire. A refresh token rotated by the server is used for the next refresh.
+/// A token request that takes longer than 30 seconds fails with [`Error::Timeout`], see [`OAuth2TokenProvider::timeout`].
+pub struct OAuth2TokenProvider {
+    client: Client,
+    token_url: Url,
+    config: OAuth2Config,
+    grant: Grant,
+    timeout: Duration,
+    cache: TokenCache,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default, deserialize_with = "seconds")]
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+}
+
+/// Some servers send `expires_in` as a string
+fn seconds<'de, D>(deserializer: D) -> std::result::Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seconds {
+        Number(u64),
+        Text(String),
+    }
+
+    match Option::<Seconds>::deserialize(deserializer)? {
+        Some(Seconds::Number(seconds)) => Ok(Some(seconds)),
+        Some(Seconds::Text(text)) => text
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+impl OAuth2TokenProvider {
+    /// Provider using the client credentials grant
+    pub fn client_credentials(config: OAuth2Config) -> Result<Self> {
+        Self::new(config, Grant::ClientCredentials)
+    }
+
+    /// Provider using the refresh token grant
+    pub fn refresh_token(config: OAuth2Config, refresh_token: &str) -> Result<Self> {
+        Self::new(
+            config,
+            Grant::RefreshToken(Mutex::new(Secret::new(refresh_token))),
+        )
+    }
+
+    fn new(config: OAuth2Config, grant: Grant) -> Result<Self> {
+        Ok(Self {
+            client: token_client()?,
+            token_url: Url::parse(&config.token_url)?,
+            config,
+            grant,
+            timeout: DEFAULT_TIMEOUT,
+            cache: TokenCache::default(),
+        })
+    }
+
+    /// Refresh the token when it expires within `margin`, default is 30 seconds
+    pub fn refresh_margin(mut self, margin: Duration) -> Self {
+        self.cache.set_refresh_margin(margin);
+        self
+    }
+
+    /// Maximum time allowed for a token request, default is 30 seconds
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn form<'a>(
+        &'a self,
+        refresh_token: Option<&'a Secret>,
+        scope: &'a str,
+    ) -> Vec<(&'static str, &'a str)> {
+        let mut form = match refresh_token {
+            None => vec![("grant_type", "client_credentials")],
+            Some(refresh_token) => vec![
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token.expose()),
+            ],
+        };
+        if !scope.is_empty() {
+            form.push(("scope", scope));
+        }
+        if self.config.client_secret.is_none() {
+            form.push(("client_id", &self.config.client_id));
+        }
+        form
+    }
+
+    async fn fetch(&self) -> Result<(Secret, Option<SystemTime>)> {
+        let refresh_token = match &self.grant {
+            Grant::ClientCredentials => None,
+            Grant::RefreshToken(refresh_token) => Some(refresh_token.lock().unwrap().clone()),
+        };
+        let scope = self.config.scopes.join(" ");
+        let mut builder = self
+            .client
+            .post(self.token_url.clone())
+            .timeout(self.timeout)
+            .form(&self.form(refresh_token.as_ref(), &scope));
+        if let Some(secret) = &self.config.client_secret {
+            builder = builder.basic_auth(&self.config.client_id, Some(secret.expose()));
+        }
+        let response = builder.send().await?;
+        let status = response.status();
+        let bytes = response.bytes().await?;
+        if !status.is_success() {
+            return Err(rejected(status, &bytes));
+        }
+        // the body holds the tokens, so it is not part of the error
+        let token = serde_json::from_slice::<TokenResponse>(&bytes)
+            .map_err(|error| Error::Auth(format!("invalid token response: {error}")))?;
+        if let (Grant::RefreshToken(refresh_token), Some(rotated)) =
+            (&self.grant, token.refresh_token)
+        {
+            *refresh_token.lock().unwrap() = Secret::new(rotated);
+        }
+        let access_token = Secret::new(token.access_token);
+        let expires = token
+            .expires_in
+            .map(|seconds| SystemTime::now() + Duration::from_secs(seconds))
+            .or_else(|| crate::token::expires(access_token.expose()));
+        Ok((access_token, expires))
+    }
+}
+
+impl Authenticator for OAuth2TokenProvider {
+    fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+        authenticate_with_token(self, request)
+    }
+
+    fn invalidate(&self) -> bool {
+        TokenProvider::invalidate(self);
+        true
+    }
+}
+
+impl TokenProvider for OAuth2TokenProvider {
+    fn token(&self) -> BoxFuture<'_, Result<Secret>> {
+        self.cache.token(|| self.fetch()).boxed()
+    }
+
+    fn invalidate(&self) {
+        self.cache.invalidate();
+    }
+}
+
+/// Authentication error including the OAuth2 error code and description if the server provided them
+fn rejected(status: StatusCode, body: &[u8]) -> Error {
+    let reason = serde_json::from_slice::<ErrorResponse>(body)
+        .map(|error| match error.error_description {
+            Some(description) => format!(": {}, {description}", error.error),
+            None => format!(": {}", error.error),
+        })
+        .unwrap_or_default();
+    Error::Auth(format!(
+        "token request rejected with status {status}{reason}"
+    ))
+}
@@ -0,0 +1,298 @@
+use crate::{ApiClient, ApiRequestBuilder, Error, Result, error_for_status, path};
+use futures_util::{Stream, TryStreamExt, stream};
+use reqwest::{
+    Url,
+    header::{HeaderMap, LINK},
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+
+const DEFAULT_MAX_PAGES: usize = 1000;
+
+/// How the pages of a collection are requested, used with [`ApiClient::paginate`]
+///
+/// Every page is a json array of items, or an object with the array at [`Pagination::items`].
+/// No more than [`Pagination::max_pages`] pages are requested, so that a server that keeps
+/// returning a next page cannot make the stream endless.
+///
+/// # Example
+///
+/// Return the posts of [Json Placeholder](https://jsonplaceholder.typicode.com/) ten at a time
+///
+/// ```
+/// # use rest_json_client::{ApiClientBuilder, Error, Pagination};
+/// # use json_placeholder_data::posts::Post;
+/// # use futures_util::TryStreamExt;
+/// #
+/// # tokio_test::block_on(async {
+///     let base = "https://jsonplaceholder.typicode.com/";
+///     let client = ApiClientBuilder::new(base).build()?;
+///     let posts = client
+///         .paginate::<Post>("posts", Pagination::page("_page", "_limit", 10))
+///         .try_collect::<Vec<_>>()
+///         .await?;
+///
+/// #     assert_eq!(posts.len(), 100);
+/// #     Ok::<(), Error>(())
+/// # });
+/// ```
+#[derive(Clone, Debug)]
+pub struct Pagination {
+    strategy: Strategy,
+    items: String,
+    max_pages: usize,
+}
+
+#[derive(Clone, Debug)]
+enum Strategy {
+    Page {
+        page: String,
+        limit: String,
+        size: usize,
+    },
+    Offset {
+        offset: String,
+        limit: String,
+        size: usize,
+    },
+    Cursor {
+        param: String,
+        pointer: String,
+    },
+    Link,
+}
+
+impl Pagination {
+    fn new(strategy: Strategy) -> Self {
+        Self {
+            strategy,
+            items: String::new(),
+            max_pages: DEFAULT_MAX_PAGES,
+        }
+    }
+
+    /// Number the pages from 1 in the query parameter `page` and ask for `size` items per page
+    /// in the query parameter `limit`. A page with less than `size` items is the last one.
+    pub fn page(page: &str, limit: &str, size: usize) -> Self {
+        Self::new(Strategy::Page {
+            page: page.to_owned(),
+            limit: limit.to_owned(),
+            size: size.max(1),
+        })
+    }
+
+    /// Pass the number of items already received in the query parameter `offset` and ask for
+    /// `size` items per page in the query parameter `limit`. A page with less than `size` items is the last one.
+    pub fn offset(offset: &str, limit: &str, size: usize) -> Self {
+        Self::new(Strategy::Offset {
+            offset: offset.to_owned(),
+            limit: limit.to_owned(),
+            size: size.max(1),
+        })
+    }
+
+    /// Read the cursor of the next page from the response body at the
+    /// [json pointer](https://www.rfc-editor.org/rfc/rfc6901) `pointer` and pass it in the query parameter `param`.
+    /// A missing, null or empty cursor ends the collection.
+    pub fn cursor(param: &str, pointer: &str) -> Self {
+        Self::new(Strategy::Cursor {
+            param: param.to_owned(),
+            pointer: pointer.to_owned(),
+        })
+    }
+
+    /// Follow the [RFC 8288](https://www.rfc-editor.org/rfc/rfc8288) `Link` header with `rel="next"`.
+    /// A link outside of the prefix of the client, like a link to another server, ends the stream with [`Error::Url`].
+    pub fn link() -> Self {
+        Self::new(Strategy::Link)
+    }
+
+    /// The json pointer of the array of items in a page, like `/data`. By default the page is the array.
+    pub fn items(mut self, pointer: &str) -> Self {
+        self.items = pointer.to_owned();
+        self
+    }
+
+    /// Stop after the given number of pages, even if the server has more
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages;
+        self
+    }
+
+    fn query(&self, base: &Url, pages: usize) -> Url {
+        let mut url = base.clone();
+        match &self.strategy {
+            Strategy::Page { page, limit, size } => {
+                url.query_pairs_mut()
+                    .append_pair(page, &(pages + 1).to_string())
+                    .append_pair(limit, &size.to_string());
+            }
+            Strategy::Offset {
+                offset,
+                limit,
+                size,
+            } => {
+                url.query_pairs_mut()
+                    .append_pair(offset, &(pages * size).to_string())
+                    .append_pair(limit, &size.to_string());
+            }
+            Strategy::Cursor { .. } | Strategy::Link => {}
+        }
+        url
+    }
+
+    /// The url of the page after the one at `url`, which returned `count` items
+    fn next(
+        &self,
+        base: &Url,
+        url: &Url,
+        pages: usize,
+        count: usize,
+        body: &Value,
+        headers: &HeaderMap,
+    ) -> Result<Option<Url>> {
+        match &self.strategy {
+            Strategy::Page { size, .. } | Strategy::Offset { size, .. } => {
+                Ok((count >= *size).then(|| self.query(base, pages)))
+            }
+            Strategy::Cursor { param, pointer } => {
+                let cursor = match body.pointer(pointer) {
+                    Some(Value::String(cursor)) if !cursor.is_empty() => cursor.clone(),
+                    Some(Value::Number(cursor)) => cursor.to_string(),
+                    _ => return Ok(None),
+                };
+                let mut url = base.clone();
+                url.query_pairs_mut().append_pair(param, &cursor);
+                Ok(Some(url))
+            }
+            Strategy::Link => {
+                let Some(link) = next_link(headers) else {
+                    return Ok(None);
+                };
+                Ok(Some(url.join(&link)?))
+            }
+        }
+    }
+}
+
+/// The state carried from one page to the next
+struct Pages<'a> {
+    client: &'a ApiClient,
+    base: Url,
+    pagination: Pagination,
+    /// A next page that cannot be requested is reported after the items of the current page
+    next: Option<Result<Url>>,
+    pages: usize,
+}
+
+impl Pages<'_> {
+    async fn next_page<T>(mut self) -> Result<Option<(Vec<T>, Self)>>
+    where
+        T: DeserializeOwned,
+    {
+        match self.next.take().transpose()? {
+            Some(url) => Ok(Some((self.fetch(url).await?, self))),
+            None => Ok(None),
+        }
+    }
+
+    async fn fetch<T>(&mut self, url: Url) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let builder = self.client.client.get(url.clone());
+        let response = ApiRequestBuilder::new(self.client, Ok(builder), true)
+            .send()
+            .await?;
+        let response = error_for_status(response).await?;
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await?;
+        let mut body = serde_json::from_slice::<Value>(&bytes)
+            .map_err(|error| Error::deserialize(error, &bytes))?;
+        let items = body
+            .pointer_mut(&self.pagination.items)
+            .map(Value::take)
+            .ok_or_else(|| {
+                serde::de::Error::custom(format!("no items at {:?}", self.pagination.items))
+            })
+            .and_then(Vec::<T>::deserialize)
+            .map_err(|error| Error::deserialize(error, &bytes))?;
+
+        self.pages += 1;
+        self.next = if self.pages < self.pagination.max_pages {
+            self.pagination
+                .next(&self.base, &url, self.pages, items.len(), &body, &headers)
+                .and_then(|next| within(&self.client.prefix, next))
+                .transpose()
+        } else {
+            None
+        };
+        Ok(items)
+    }
+}
+
+pub(crate) fn paginate<'a, T>(
+    client: &'a ApiClient,
+    base: Result<Url>,
+    pagination: Pagination,
+) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: DeserializeOwned + 'a,
+{
+    let pages = base.map(|base| Pages {
+        client,
+        next: (pagination.max_pages > 0).then(|| Ok(pagination.query(&base, 0))),
+        base,
+        pagination,
+        pages: 0,
+    });
+    stream::once(async { pages })
+        .and_then(|pages| async { Ok(stream::try_unfold(pages, Pages::next_page::<T>)) })
+        .try_flatten()
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+/// The request is authenticated, so the next page must not be on another server or api
+fn within(prefix: &Url, next: Option<Url>) -> Result<Option<Url>> {
+    match next {
+        Some(next) if !path::is_within(prefix, &next) => Err(Error::Url(format!(
+            "next page {next} is outside of {prefix}"
+        ))),
+        next => Ok(next),
+    }
+}
+
+/// The target of the first link with relation type `next`
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(|value| {
+            let mut rest = value;
+            while let Some(start) = rest.find('<') {
+                let end = start + rest[start..].find('>')?;
+                let parameters_end = rest[end..].find('<').map_or(rest.len(), |i| end + i);
+                if rest[end + 1..parameters_end]
+                    .split([';', ','])
+                    .any(is_rel_next)
+                {
+                    return Some(rest[start + 1..end].to_owned());
+                }
+                rest = &rest[parameters_end..];
+            }
+            None
+        })
+}
+
+fn is_rel_next(parameter: &str) -> bool {
+    parameter.split_once('=').is_some_and(|(name, value)| {
+        name.trim().eq_ignore_ascii_case("rel")
+            && value
+                .trim()
+                .trim_matches('"')
+                .split_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("next"))
+    })
+}
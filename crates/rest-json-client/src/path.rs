@@ -0,0 +1,71 @@
+use crate::{Error, Result};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use reqwest::Url;
+
+/// Everything except the unreserved characters of RFC 3986 is encoded
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Join segments into a relative path, percent-encoding every segment.
+/// Use this for user supplied values, so that a `/`, `?` or `#` in a value
+/// cannot change the meaning of the url. A `.` or `..` segment cannot be encoded,
+/// urls treat `%2E%2E` like `..`, so it is refused with [`Error::Url`].
+///
+/// # Example
+///
+/// ```
+/// # use rest_json_client::path_segments;
+/// #
+/// assert_eq!(
+///     path_segments(["lyric", "Daar bij de waterkant?"]).unwrap(),
+///     "lyric/Daar%20bij%20de%20waterkant%3F"
+/// );
+/// assert!(path_segments(["lyric", ".."]).is_err());
+/// ```
+pub fn path_segments<I>(segments: I) -> Result<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    segments
+        .into_iter()
+        .map(|segment| match segment.as_ref() {
+            segment @ ("." | "..") => Err(Error::Url(format!("{segment} is not a path segment"))),
+            segment => Ok(utf8_percent_encode(segment, SEGMENT).to_string()),
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|segments| segments.join("/"))
+}
+
+/// Parse the prefix, making sure its path ends with a slash so that joining keeps the last segment
+pub(crate) fn parse_prefix(prefix: &str) -> Result<Url, url::ParseError> {
+    let mut url = Url::parse(prefix)?;
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url)
+}
+
+/// Join a path to the prefix, a leading slash does not discard the path of the prefix.
+/// An absolute url, or a path that ends up outside of the prefix, is refused,
+/// so that credentials are never sent to another server or another api.
+pub(crate) fn join(prefix: &Url, path: &str) -> Result<Url> {
+    let outside = || Error::Url(format!("{path} is outside of {prefix}"));
+    if path.starts_with("//") || Url::parse(path).is_ok() {
+        return Err(outside());
+    }
+    let url = prefix.join(path.trim_start_matches('/'))?;
+    if is_within(prefix, &url) {
+        Ok(url)
+    } else {
+        Err(outside())
+    }
+}
+
+/// The url is on the same server as the prefix and its path starts with the path of the prefix
+pub(crate) fn is_within(prefix: &Url, url: &Url) -> bool {
+    url.origin() == prefix.origin() && url.path().starts_with(prefix.path())
+}
@@ -0,0 +1,55 @@
+use reqwest::header::{CONTENT_TYPE, HeaderMap};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt::{Display, Formatter};
+
+const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Problem details for HTTP APIs as described in [RFC 9457](https://www.rfc-editor.org/rfc/rfc9457)
+///
+/// Servers returning `application/problem+json` on failure have their explanation
+/// available through [`StatusError::problem`](crate::StatusError::problem).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ProblemDetails {
+    /// Uri reference that identifies the problem type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    /// Short, human readable summary of the problem type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The status code generated by the origin server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Human readable explanation specific to this occurrence of the problem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Uri reference that identifies this occurrence of the problem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Extension members
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+impl ProblemDetails {
+    pub(crate) fn from_response(headers: &HeaderMap, body: &str) -> Option<Self> {
+        headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .filter(|mime| mime.trim().eq_ignore_ascii_case(PROBLEM_JSON))
+            .and_then(|_| serde_json::from_str(body).ok())
+    }
+}
+
+impl Display for ProblemDetails {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let parts = [self.title.as_deref(), self.detail.as_deref()];
+        let text = parts.into_iter().flatten().collect::<Vec<_>>().join(": ");
+        if text.is_empty() {
+            write!(f, "{}", self.r#type.as_deref().unwrap_or("about:blank"))
+        } else {
+            write!(f, "{text}")
+        }
+    }
+}
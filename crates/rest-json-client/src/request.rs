@@ -0,0 +1,193 @@
+use crate::{
+    ApiClient, Error, Multipart, ResponseHead, Result, error_for_status, json, json_stream,
+};
+use bytes::Bytes;
+use futures_util::Stream;
+use reqwest::{
+    RequestBuilder,
+    header::{HeaderName, HeaderValue},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use std::time::Duration;
+
+const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+
+/// Builder for a single request, created with [`ApiClient::request`]
+///
+/// # Example
+///
+/// Try to return the posts of a specific user from [Json Placeholder](https://jsonplaceholder.typicode.com/)
+///
+/// ```
+/// # use rest_json_client::{ApiClientBuilder, Error, Method};
+/// # use json_placeholder_data::posts::Post;
+/// # use std::time::Duration;
+/// #
+/// # tokio_test::block_on(async {
+///     let base = "https://jsonplaceholder.typicode.com/";
+///     let posts = ApiClientBuilder::new(base)
+///         .build()?
+///         .request(Method::GET, "posts")
+///         .query(&[("userId", 1)])
+///         .timeout(Duration::from_secs(5))
+///         .send_json::<Vec<Post>>()
+///         .await?;
+///
+/// #     assert_eq!(posts.len(), 10);
+/// #     Ok::<(), Error>(())
+/// # });
+/// ```
+pub struct ApiRequestBuilder<'a> {
+    client: &'a ApiClient,
+    builder: Result<RequestBuilder>,
+    idempotent: bool,
+    route: Option<String>,
+}
+
+impl<'a> ApiRequestBuilder<'a> {
+    pub(crate) fn new(
+        client: &'a ApiClient,
+        builder: Result<RequestBuilder>,
+        idempotent: bool,
+    ) -> Self {
+        Self {
+            client,
+            builder,
+            idempotent,
+            route: None,
+        }
+    }
+
+    fn map(mut self, f: impl FnOnce(RequestBuilder) -> RequestBuilder) -> Self {
+        self.builder = self.builder.map(f);
+        self
+    }
+
+    /// Append the serialized object to the query string of the url
+    pub fn query<Q>(self, query: &Q) -> Self
+    where
+        Q: Serialize + ?Sized,
+    {
+        self.map(|builder| builder.query(query))
+    }
+
+    /// Add a header to the request
+    pub fn header<K, V>(self, key: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        self.map(|builder| builder.header(key, value))
+    }
+
+    /// Use the serialized object as json body of the request
+    pub fn json<T>(self, body: &T) -> Self
+    where
+        T: Serialize + ?Sized,
+    {
+        self.map(|builder| builder.json(body))
+    }
+
+    /// Use the form as `multipart/form-data` body of the request
+    pub fn multipart(mut self, form: Multipart) -> Self {
+        self.builder = self
+            .builder
+            .and_then(|builder| Ok(builder.multipart(form.into_form()?)));
+        self
+    }
+
+    /// Override the overall timeout of the client for this request
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.map(|builder| builder.timeout(timeout))
+    }
+
+    /// Attach an `Idempotency-Key` header. This allows the request to be retried by the retry policy,
+    /// even if it is a post and [`RetryPolicy::retry_post`](crate::RetryPolicy::retry_post) is disabled.
+    pub fn idempotency_key(mut self, key: &str) -> Self {
+        self.idempotent = true;
+        self.header(IDEMPOTENCY_KEY, key)
+    }
+
+    /// The path template of the request, like `lyric/{id}`, recorded in traces and metrics.
+    /// Requests without a route are recorded with route `unknown`.
+    pub fn route(mut self, route: &str) -> Self {
+        self.route = Some(route.to_owned());
+        self
+    }
+
+    pub(crate) async fn send(self) -> Result<reqwest::Response> {
+        self.client
+            .send(self.builder?, self.idempotent, self.route.as_deref())
+            .await
+    }
+
+    /// Send the request and read the response with `read`
+    async fn send_with<T, F, Fut>(self, read: F) -> Result<T>
+    where
+        F: FnOnce(reqwest::Response) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.client
+            .send_with(self.builder?, self.idempotent, self.route.as_deref(), read)
+            .await
+    }
+
+    /// Send the request and deserialize the json response body
+    pub async fn send_json<R>(self) -> Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        let client = self.client;
+        self.send_with(|response| async move { json(client.check_status(response).await?).await })
+            .await
+    }
+
+    /// Send the request and deserialize the items of a json array or newline delimited json
+    /// response body while it is received, without holding the whole body in memory
+    pub fn send_stream<R>(self) -> impl Stream<Item = Result<R>> + 'a
+    where
+        R: DeserializeOwned + 'a,
+    {
+        let client = self.client;
+        json_stream::items(async move { client.check_status(self.send().await?).await })
+    }
+
+    /// Send the request and ignore the response body.
+    /// A non success status is always an error, also with [`ApiClientBuilder::error_for_status`](crate::ApiClientBuilder::error_for_status) disabled.
+    pub async fn send_empty(self) -> Result<()> {
+        self.send_with(|response| async { error_for_status(response).await.map(|_| ()) })
+            .await
+    }
+
+    /// Send the request and return the response body as text.
+    /// A non success status is always an error, also with [`ApiClientBuilder::error_for_status`](crate::ApiClientBuilder::error_for_status) disabled.
+    pub async fn send_text(self) -> Result<String> {
+        self.send_with(|response| async {
+            let response = error_for_status(response).await?;
+            response.text().await.map_err(Error::from)
+        })
+        .await
+    }
+
+    /// Send the request and return the status and headers of the response.
+    /// A non success status is returned as is and not turned into an error.
+    pub async fn send_head(self) -> Result<ResponseHead> {
+        let response = self.send().await?;
+        Ok(ResponseHead::new(
+            response.status(),
+            response.headers().clone(),
+        ))
+    }
+
+    /// Send the request and return the raw response body.
+    /// A non success status is always an error, also with [`ApiClientBuilder::error_for_status`](crate::ApiClientBuilder::error_for_status) disabled.
+    pub async fn send_bytes(self) -> Result<Bytes> {
+        self.send_with(|response| async {
+            let response = error_for_status(response).await?;
+            response.bytes().await.map_err(Error::from)
+        })
+        .await
+    }
+}
@@ -0,0 +1,39 @@
+use reqwest::{
+    Method, StatusCode,
+    header::{ALLOW, HeaderMap},
+};
+
+/// Status and headers of a response without a body, as returned by
+/// [`ApiClient::head`](crate::ApiClient::head) and [`ApiClient::options`](crate::ApiClient::options)
+#[derive(Clone, Debug)]
+pub struct ResponseHead {
+    status: StatusCode,
+    headers: HeaderMap,
+}
+
+impl ResponseHead {
+    pub(crate) fn new(status: StatusCode, headers: HeaderMap) -> Self {
+        Self { status, headers }
+    }
+
+    /// The status code returned by the server
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The headers returned by the server
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The methods listed in the `Allow` header
+    pub fn allow(&self) -> Vec<Method> {
+        self.headers
+            .get_all(ALLOW)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|method| method.trim().parse().ok())
+            .collect()
+    }
+}
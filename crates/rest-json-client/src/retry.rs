@@ -0,0 +1,126 @@
+use crate::Error;
+use reqwest::{
+    Response, StatusCode,
+    header::{HeaderMap, RETRY_AFTER},
+};
+use std::time::{Duration, SystemTime};
+
+/// Determines when and how often a failed request is repeated
+///
+/// Get, put and delete requests are retried on connection errors and on the
+/// status codes 429, 502, 503 and 504. Post requests are only retried when they carry an
+/// [`idempotency_key`](crate::ApiRequestBuilder::idempotency_key) or when explicitly
+/// enabled with [`RetryPolicy::retry_post`]. When the server asks to wait
+/// longer than the maximum backoff with `Retry-After`, its response is returned without retrying.
+///
+/// # Example
+///
+/// ```
+/// # use rest_json_client::{ApiClientBuilder, Error, RetryPolicy};
+/// # use std::time::Duration;
+/// #
+/// let client = ApiClientBuilder::new("https://jsonplaceholder.typicode.com/")
+///     .retry_policy(
+///         RetryPolicy::new(5)
+///             .initial_backoff(Duration::from_millis(200))
+///             .max_backoff(Duration::from_secs(5)),
+///     )
+///     .build()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retry_post: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            retry_post: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy that makes at most `max_attempts` attempts per call
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Default::default()
+        }
+    }
+
+    /// Upper bound of the delay before the first retry
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Upper bound of the delay between two attempts, also the longest `Retry-After` that is waited for
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Also retry post requests without an idempotency key. Only enable this if the server handles duplicate posts safely.
+    pub fn retry_post(mut self, retry_post: bool) -> Self {
+        self.retry_post = retry_post;
+        self
+    }
+
+    pub(crate) fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub(crate) fn retries_post(&self) -> bool {
+        self.retry_post
+    }
+
+    /// Exponential backoff with full jitter, a `Retry-After` header takes precedence.
+    /// `None` when `Retry-After` is longer than the maximum backoff.
+    pub(crate) fn delay(&self, attempt: u32, headers: Option<&HeaderMap>) -> Option<Duration> {
+        match headers.and_then(retry_after) {
+            Some(delay) => (delay <= self.max_backoff).then_some(delay),
+            None => {
+                let ceiling = self
+                    .initial_backoff
+                    .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+                    .min(self.max_backoff);
+                Some(ceiling.mul_f64(fastrand::f64()))
+            }
+        }
+    }
+}
+
+pub(crate) fn is_retryable_error(error: &Error) -> bool {
+    matches!(error, Error::Transport(error) | Error::Timeout(error) if error.is_connect())
+}
+
+pub(crate) fn is_retryable_response(response: &Response) -> bool {
+    matches!(
+        response.status(),
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    value
+        .parse::<u64>()
+        .map(Duration::from_secs)
+        .ok()
+        .or_else(|| {
+            httpdate::parse_http_date(value)
+                .ok()
+                .map(|date| date.duration_since(SystemTime::now()).unwrap_or_default())
+        })
+}
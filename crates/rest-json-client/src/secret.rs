@@ -0,0 +1,51 @@
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+use zeroize::Zeroizing;
+
+/// A credential such as a password, token or api key
+///
+/// The value is zeroized when the last clone is dropped and is never shown in the [`Debug`] output.
+/// Clones share the same allocation, so the secret is not copied when an [`ApiClient`](crate::ApiClient) uses it.
+///
+/// # Example
+///
+/// ```
+/// # use rest_json_client::Secret;
+/// #
+/// let password = Secret::new("s3cr3t");
+/// assert_eq!(password.expose(), "s3cr3t");
+/// assert_eq!(format!("{password:?}"), "Secret(<redacted>)");
+/// ```
+#[derive(Clone)]
+pub struct Secret(Arc<Zeroizing<String>>);
+
+impl Secret {
+    pub fn new<S: Into<String>>(value: S) -> Self {
+        Self(Arc::new(Zeroizing::new(value.into())))
+    }
+
+    /// The value of the secret. Take care not to copy or log it.
+    pub fn expose(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{Authenticator, Error, Result};
+use base64::{Engine, prelude::BASE64_STANDARD};
+use futures_util::{FutureExt, future::BoxFuture};
+use hmac::{Hmac, Mac};
+use reqwest::{
+    Method, Request,
+    header::{HeaderMap, HeaderName, HeaderValue},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use zeroize::Zeroizing;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SIGNATURE: HeaderName = HeaderName::from_static("signature");
+const TIMESTAMP: HeaderName = HeaderName::from_static("x-timestamp");
+const CONTENT_SHA256: HeaderName = HeaderName::from_static("x-content-sha256");
+
+/// The parts of a request covered by the signature
+pub struct SigningInput<'a> {
+    pub method: &'a Method,
+    /// Path including the query string, if any
+    pub path: &'a str,
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    /// Hex encoded sha256 digest of the body
+    pub body_digest: &'a str,
+}
+
+/// How the [`SigningInput`] is turned into the string that is signed
+#[derive(Clone)]
+pub enum Canonicalization {
+    /// Method, path, timestamp and body digest, separated by newlines
+    Newline,
+    /// A custom canonical form
+    Custom(Arc<dyn Fn(&SigningInput<'_>) -> String + Send + Sync>),
+}
+
+impl Canonicalization {
+    fn canonicalize(&self, input: &SigningInput<'_>) -> String {
+        match self {
+            Canonicalization::Newline => format!(
+                "{}\n{}\n{}\n{}",
+                input.method, input.path, input.timestamp, input.body_digest
+            ),
+            Canonicalization::Custom(f) => f(input),
+        }
+    }
+}
+
+/// Signs every request with HMAC-SHA256 over method, path, timestamp and body digest
+///
+/// The base64 encoded signature, the timestamp and the body digest are sent in the
+/// `Signature`, `X-Timestamp` and `X-Content-SHA256` headers unless configured otherwise.
+/// A test server can check requests with [`HmacSigner::verify_request`].
+///
+/// # Example
+///
+/// ```
+/// # use rest_json_client::{ApiClientBuilder, Authentication, Error, HmacSigner, Method};
+/// # use rest_json_client::header::HeaderName;
+/// #
+/// let signer = HmacSigner::new(b"shared secret")
+///     .signature_header(HeaderName::from_static("x-signature"));
+/// let signature = signer.sign(&Method::GET, "/lyric", 1_700_000_000, b"");
+/// assert!(signer.verify(&Method::GET, "/lyric", 1_700_000_000, b"", &signature));
+///
+/// let client = ApiClientBuilder::new("https://www.paulmin.nl/lipl/api/v1/")
+///     .authentication(Authentication::new_hmac(signer))
+///     .build()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone)]
+pub struct HmacSigner {
+    secret: Arc<Zeroizing<Vec<u8>>>,
+    signature_header: HeaderName,
+    timestamp_header: HeaderName,
+    digest_header: HeaderName,
+    canonicalization: Canonicalization,
+}
+
+impl HmacSigner {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            secret: Arc::new(Zeroizing::new(secret.to_vec())),
+            signature_header: SIGNATURE,
+            timestamp_header: TIMESTAMP,
+            digest_header: CONTENT_SHA256,
+            canonicalization: Canonicalization::Newline,
+        }
+    }
+
+    /// Header containing the signature, default is `Signature`
+    pub fn signature_header(mut self, name: HeaderName) -> Self {
+        self.signature_header = name;
+        self
+    }
+
+    /// Header containing the timestamp, default is `X-Timestamp`
+    pub fn timestamp_header(mut self, name: HeaderName) -> Self {
+        self.timestamp_header = name;
+        self
+    }
+
+    /// Header containing the body digest, default is `X-Content-SHA256`
+    pub fn digest_header(mut self, name: HeaderName) -> Self {
+        self.digest_header = name;
+        self
+    }
+
+    pub fn canonicalization(mut self, canonicalization: Canonicalization) -> Self {
+        self.canonicalization = canonicalization;
+        self
+    }
+
+    fn mac(&self, method: &Method, path: &str, timestamp: u64, body: &[u8]) -> HmacSha256 {
+        let body_digest = hex::encode(Sha256::digest(body));
+        let input = SigningInput {
+            method,
+            path,
+            timestamp,
+            body_digest: &body_digest,
+        };
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("hmac accepts any key size");
+        mac.update(self.canonicalization.canonicalize(&input).as_bytes());
+        mac
+    }
+
+    /// The base64 encoded signature for a request
+    pub fn sign(&self, method: &Method, path: &str, timestamp: u64, body: &[u8]) -> String {
+        BASE64_STANDARD.encode(
+            self.mac(method, path, timestamp, body)
+                .finalize()
+                .into_bytes(),
+        )
+    }
+
+    /// Check a base64 encoded signature in constant time
+    pub fn verify(
+        &self,
+        method: &Method,
+        path: &str,
+        timestamp: u64,
+        body: &[u8],
+        signature: &str,
+    ) -> bool {
+        BASE64_STANDARD.decode(signature).is_ok_and(|signature| {
+            self.mac(method, path, timestamp, body)
+                .verify_slice(&signature)
+                .is_ok()
+        })
+    }
+
+    /// Check the signature and body digest headers of a received request
+    pub fn verify_request(
+        &self,
+        method: &Method,
+        path: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> bool {
+        let header = |name: &HeaderName| headers.get(name).and_then(|value| value.to_str().ok());
+        let digest_matches =
+            header(&self.digest_header) == Some(hex::encode(Sha256::digest(body)).as_str());
+        let timestamp = header(&self.timestamp_header).and_then(|value| value.parse().ok());
+        match (timestamp, header(&self.signature_header)) {
+            (Some(timestamp), Some(signature)) => {
+                digest_matches && self.verify(method, path, timestamp, body, signature)
+            }
+            _ => false,
+        }
+    }
+
+    fn sign_request(&self, request: &mut Request) -> Result<()> {
+        let body = match request.body() {
+            Some(body) => body
+                .as_bytes()
+                .ok_or_else(|| Error::Auth("cannot sign a streaming body".to_owned()))?,
+            None => &[],
+        };
+        let path = match request.url().query() {
+            Some(query) => format!("{}?{query}", request.url().path()),
+            None => request.url().path().to_owned(),
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let signature = self.sign(request.method(), &path, timestamp, body);
+        let digest = hex::encode(Sha256::digest(body));
+
+        let headers = request.headers_mut();
+        headers.insert(self.timestamp_header.clone(), HeaderValue::from(timestamp));
+        headers.insert(
+            self.digest_header.clone(),
+            HeaderValue::try_from(digest).expect("hex is a valid header value"),
+        );
+        let mut signature =
+            HeaderValue::try_from(signature).expect("base64 is a valid header value");
+        signature.set_sensitive(true);
+        headers.insert(self.signature_header.clone(), signature);
+        Ok(())
+    }
+}
+
+impl Authenticator for HmacSigner {
+    fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+        futures_util::future::ready(self.sign_request(request)).boxed()
+    }
+
+    fn signs_every_request(&self) -> bool {
+        true
+    }
+}
+
+impl Debug for HmacSigner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacSigner")
+            .field("signature_header", &self.signature_header)
+            .field("timestamp_header", &self.timestamp_header)
+            .field("digest_header", &self.digest_header)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    Authenticator, Error, Result, Secret, StatusCode, authentication::authenticate_with_token,
+    error_for_status,
+};
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use futures_util::{FutureExt, future::BoxFuture};
+use reqwest::{Client, Request, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use zeroize::Zeroizing;
+
+const SIGNATURE: &str = "Signature";
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(30);
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Source of bearer tokens, consulted by [`ApiClient`](crate::ApiClient) for every request
+///
+/// When the server responds with `401 Unauthorized` the client calls [`TokenProvider::invalidate`]
+/// and repeats the request once with a fresh token.
+pub trait TokenProvider: Send + Sync {
+    /// Return a valid token, obtaining a new one when needed
+    fn token(&self) -> BoxFuture<'_, Result<Secret>>;
+    /// Forget the current token, so that the next call to [`TokenProvider::token`] obtains a new one
+    fn invalidate(&self);
+}
+
+/// Obtains a Json Web Token by posting a signed object, like [`ApiClient::token_request`](crate::ApiClient::token_request)
+///
+/// The token is cached and refreshed shortly before the `exp` claim of the token passes.
+/// A token request that takes longer than 30 seconds fails with [`Error::Timeout`], see [`SignedTokenProvider::timeout`].
+///
+/// # Example
+///
+/// ```
+/// # use rest_json_client::{ApiClientBuilder, Authentication, Error, SignedTokenProvider};
+/// # use std::collections::HashMap;
+/// #
+/// let provider = SignedTokenProvider::new(
+///     "https://www.paulmin.nl/lipl/api/v1/token",
+///     "signature",
+///     HashMap::from([("username", "paul")]),
+/// )?;
+/// let client = ApiClientBuilder::new("https://www.paulmin.nl/lipl/api/v1/")
+///     .authentication(Authentication::new_token_provider(provider))
+///     .build()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct SignedTokenProvider {
+    client: Client,
+    url: Url,
+    signature: Secret,
+    object: Value,
+    timeout: Duration,
+    cache: TokenCache,
+}
+
+impl SignedTokenProvider {
+    pub fn new<T>(url: &str, signature: &str, object: T) -> Result<Self>
+    where
+        T: Serialize,
+    {
+        let object = serde_json::to_value(object)
+            .map_err(|error| Error::Auth(format!("invalid token request object: {error}")))?;
+        Ok(Self {
+            client: token_client()?,
+            url: Url::parse(url)?,
+            signature: Secret::new(signature),
+            object,
+            timeout: DEFAULT_TIMEOUT,
+            cache: TokenCache::default(),
+        })
+    }
+
+    /// Refresh the token when it expires within `margin`, default is 30 seconds
+    pub fn refresh_margin(mut self, margin: Duration) -> Self {
+        self.cache.set_refresh_margin(margin);
+        self
+    }
+
+    /// Maximum time allowed for a token request, default is 30 seconds
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    async fn fetch(&self) -> Result<(Secret, Option<SystemTime>)> {
+        let response = self
+            .client
+            .post(self.url.clone())
+            .timeout(self.timeout)
+            .header(SIGNATURE, self.signature.expose())
+            .json(&self.object)
+            .send()
+            .await?;
+        let body = Zeroizing::new(
+            error_for_status(response)
+                .await
+                .map_err(rejected)?
+                .text()
+                .await?,
+        );
+        let token = Secret::new(body.trim());
+        let expires = expires(token.expose());
+        Ok((token, expires))
+    }
+}
+
+impl Authenticator for SignedTokenProvider {
+    fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+        authenticate_with_token(self, request)
+    }
+
+    fn invalidate(&self) -> bool {
+        TokenProvider::invalidate(self);
+        true
+    }
+}
+
+impl TokenProvider for SignedTokenProvider {
+    fn token(&self) -> BoxFuture<'_, Result<Secret>> {
+        self.cache.token(|| self.fetch()).boxed()
+    }
+
+    fn invalidate(&self) {
+        self.cache.invalidate();
+    }
+}
+
+/// Token cache shared by the built-in providers. Concurrent refreshes are serialized,
+/// so that callers waiting for a refresh use the token obtained by the first.
+pub(crate) struct TokenCache {
+    refresh_margin: Duration,
+    cached: Mutex<Option<CachedToken>>,
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+struct CachedToken {
+    token: Secret,
+    expires: Option<SystemTime>,
+}
+
+impl Default for TokenCache {
+    fn default() -> Self {
+        Self {
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+            cached: Mutex::new(None),
+            refreshing: tokio::sync::Mutex::new(()),
+        }
+    }
+}
+
+impl TokenCache {
+    pub(crate) fn set_refresh_margin(&mut self, margin: Duration) {
+        self.refresh_margin = margin;
+    }
+
+    fn valid(&self) -> Option<Secret> {
+        let deadline = SystemTime::now() + self.refresh_margin;
+        self.cached
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|cached| cached.expires.is_none_or(|expires| expires > deadline))
+            .map(|cached| cached.token.clone())
+    }
+
+    /// Return the cached token if it is still valid, otherwise store and return the result of `fetch`
+    pub(crate) async fn token<F, Fut>(&self, fetch: F) -> Result<Secret>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(Secret, Option<SystemTime>)>>,
+    {
+        if let Some(token) = self.valid() {
+            return Ok(token);
+        }
+        let _refreshing = self.refreshing.lock().await;
+        if let Some(token) = self.valid() {
+            return Ok(token);
+        }
+        let (token, expires) = fetch().await?;
+        *self.cached.lock().unwrap() = Some(CachedToken {
+            token: token.clone(),
+            expires,
+        });
+        Ok(token)
+    }
+
+    pub(crate) fn invalidate(&self) {
+        self.cached.lock().unwrap().take();
+    }
+}
+
+/// Client for token requests, which are not sent through the [`ApiClient`](crate::ApiClient),
+/// with the user agent of the library and a connect timeout
+pub(crate) fn token_client() -> Result<Client> {
+    Ok(Client::builder()
+        .user_agent(crate::DEFAULT_USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()?)
+}
+
+/// Turn a rejected token request into an authentication error
+pub(crate) fn rejected(error: Error) -> Error {
+    match error.status() {
+        Some(status @ (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)) => {
+            Error::Auth(format!("token request rejected with status {status}"))
+        }
+        _ => error,
+    }
+}
+
+/// The `exp` claim of a Json Web Token, the signature is not verified
+pub(crate) fn expires(token: &str) -> Option<SystemTime> {
+    #[derive(Deserialize)]
+    struct Claims {
+        exp: u64,
+    }
+
+    let payload = token.split('.').nth(1)?;
+    let bytes = BASE64_URL_SAFE_NO_PAD.decode(payload).ok()?;
+    let claims = serde_json::from_slice::<Claims>(&bytes).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(claims.exp))
+}
@@ -0,0 +1,139 @@
+use crate::Result;
+use reqwest::{Request, Response, header::HeaderName};
+use std::{future::Future, sync::Arc};
+
+#[cfg(feature = "tracing")]
+pub(crate) use enabled::RequestTrace;
+
+/// Spans and events are only emitted with the `tracing` feature
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct RequestTrace;
+
+#[cfg(not(feature = "tracing"))]
+impl RequestTrace {
+    pub(crate) fn new(_: &Request, _: &str, _: &Arc<[HeaderName]>) -> Self {
+        Self
+    }
+
+    pub(crate) fn instrument<F: Future>(&self, future: F) -> F {
+        future
+    }
+
+    pub(crate) fn attempt(&self, _: u32, _: &Request) {}
+
+    pub(crate) fn finish(&self, _: &Result<Response>) {}
+}
+
+#[cfg(feature = "tracing")]
+mod enabled {
+    use super::*;
+    use crate::{TraceParent, context::TRACEPARENT};
+    use reqwest::header::{AUTHORIZATION, COOKIE, HeaderMap, PROXY_AUTHORIZATION, SET_COOKIE};
+    use std::{
+        fmt::{Debug, Formatter},
+        time::Instant,
+    };
+    use tracing::{Instrument, Span, field::Empty, instrument::Instrumented};
+
+    const REDACTED: &str = "<redacted>";
+    const ALWAYS_SENSITIVE: [HeaderName; 4] =
+        [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE, SET_COOKIE];
+
+    /// A span covering all attempts of one request
+    pub(crate) struct RequestTrace {
+        span: Span,
+        start: Instant,
+        sensitive_headers: Arc<[HeaderName]>,
+    }
+
+    impl RequestTrace {
+        /// Only the path of the url is recorded, the query string may contain an api key
+        pub(crate) fn new(
+            request: &Request,
+            route: &str,
+            sensitive_headers: &Arc<[HeaderName]>,
+        ) -> Self {
+            let path = request.url().path();
+            let span = tracing::info_span!(
+                "http.request",
+                http.request.method = %request.method(),
+                http.route = route,
+                url.path = path,
+                http.request.body.size = request.body().and_then(|body| body.as_bytes()).map(<[u8]>::len),
+                http.response.status_code = Empty,
+                http.response.body.size = Empty,
+                attempt = Empty,
+                latency_ms = Empty,
+                error.type = Empty,
+                trace_id = Empty,
+            );
+            if let Some(trace_parent) = request
+                .headers()
+                .get(TRACEPARENT)
+                .and_then(|value| value.to_str().ok())
+                .and_then(TraceParent::parse)
+            {
+                span.record("trace_id", trace_parent.trace_id_hex());
+            }
+            Self {
+                span,
+                start: Instant::now(),
+                sensitive_headers: sensitive_headers.clone(),
+            }
+        }
+
+        pub(crate) fn instrument<F: Future>(&self, future: F) -> Instrumented<F> {
+            future.instrument(self.span.clone())
+        }
+
+        pub(crate) fn attempt(&self, attempt: u32, request: &Request) {
+            self.span.record("attempt", attempt);
+            tracing::debug!(
+                attempt,
+                headers = ?Redacted(request.headers(), &self.sensitive_headers),
+                "sending request"
+            );
+        }
+
+        pub(crate) fn finish(&self, result: &Result<Response>) {
+            self.span
+                .record("latency_ms", self.start.elapsed().as_millis() as u64);
+            match result {
+                Ok(response) => {
+                    self.span
+                        .record("http.response.status_code", response.status().as_u16());
+                    if let Some(size) = response.content_length() {
+                        self.span.record("http.response.body.size", size);
+                    }
+                    tracing::debug!(
+                        status = response.status().as_u16(),
+                        headers = ?Redacted(response.headers(), &self.sensitive_headers),
+                        "received response"
+                    );
+                }
+                Err(error) => {
+                    self.span.record("error.type", error.kind());
+                    tracing::debug!(%error, "request failed");
+                }
+            }
+        }
+    }
+
+    /// Headers with the values of credentials and configured sensitive headers replaced
+    struct Redacted<'a>(&'a HeaderMap, &'a [HeaderName]);
+
+    impl Debug for Redacted<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            let Redacted(headers, sensitive_headers) = self;
+            f.debug_map()
+                .entries(headers.iter().map(|(name, value)| {
+                    let redact = value.is_sensitive()
+                        || ALWAYS_SENSITIVE.contains(name)
+                        || sensitive_headers.contains(name);
+                    let value: &dyn Debug = if redact { &REDACTED } else { value };
+                    (name, value)
+                }))
+                .finish()
+        }
+    }
+}
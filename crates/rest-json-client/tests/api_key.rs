@@ -0,0 +1,79 @@
+use rest_json_client::{ApiClientBuilder, ApiKeyLocation, Authentication};
+use serde_json::Value;
+use wiremock::matchers::{header, method, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn api_key_in_header() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("X-Api-Key", "s3cr3t"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    ApiClientBuilder::new(&server.uri())
+        .authentication(Authentication::new_api_key(
+            ApiKeyLocation::Header,
+            "X-Api-Key",
+            "s3cr3t",
+        ))
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn api_key_in_query_keeps_other_parameters() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("api_key", "s3cr3t"))
+        .and(query_param("full", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    ApiClientBuilder::new(&server.uri())
+        .authentication(Authentication::new_api_key(
+            ApiKeyLocation::Query,
+            "api_key",
+            "s3cr3t",
+        ))
+        .build()
+        .unwrap()
+        .get::<Value>("lyric?full=true")
+        .await
+        .unwrap();
+}
+
+#[test]
+fn secrets_are_redacted_from_debug() {
+    for authentication in [
+        Authentication::new_api_key(ApiKeyLocation::Query, "api_key", "s3cr3t"),
+        Authentication::new_basic("paul", "s3cr3t"),
+        Authentication::new_bearer("s3cr3t"),
+    ] {
+        assert!(!format!("{authentication:?}").contains("s3cr3t"));
+    }
+}
+
+#[tokio::test]
+async fn api_key_in_query_is_not_part_of_error() {
+    let error = ApiClientBuilder::new("http://127.0.0.1:1/")
+        .authentication(Authentication::new_api_key(
+            ApiKeyLocation::Query,
+            "api_key",
+            "s3cr3t",
+        ))
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap_err();
+
+    assert!(!format!("{error:?}").contains("s3cr3t"));
+    assert!(!error.to_string().contains("s3cr3t"));
+    assert!(error.to_string().contains("127.0.0.1:1/lyric"));
+}
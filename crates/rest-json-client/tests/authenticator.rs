@@ -0,0 +1,67 @@
+use futures_util::{FutureExt, future::BoxFuture};
+use rest_json_client::{ApiClientBuilder, Authenticator, Request, Result, header::HeaderValue};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU32, Ordering};
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Sends a session header that changes every time the session is invalidated
+#[derive(Default)]
+struct Session {
+    generation: AtomicU32,
+}
+
+impl Authenticator for Session {
+    fn authenticate<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        request
+            .headers_mut()
+            .insert("X-Session", HeaderValue::from(generation));
+        futures_util::future::ok(()).boxed()
+    }
+
+    fn invalidate(&self) -> bool {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        true
+    }
+}
+
+#[tokio::test]
+async fn custom_authenticator_is_applied() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("X-Session", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = ApiClientBuilder::new(&server.uri())
+        .authentication(Session::default())
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+}
+
+#[tokio::test]
+async fn custom_authenticator_is_invalidated_on_unauthorized() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("X-Session", "0"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(header("X-Session", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = ApiClientBuilder::new(&server.uri())
+        .authentication(Session::default())
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+}
@@ -0,0 +1,319 @@
+mod common;
+
+use rest_json_client::{
+    ApiKeyLocation, Authentication, DiskStore, HmacSigner, MemoryStore, Method, ResponseCache,
+};
+use serde_json::{Value, json};
+use std::time::Duration;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_etag(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(header("If-None-Match", r#""v1""#))
+        .respond_with(ResponseTemplate::new(304))
+        .with_priority(1)
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", r#""v1""#)
+                .set_body_json(json!({"title": "Roodkapje"})),
+        )
+        .mount(server)
+        .await;
+}
+
+async fn conditional_requests(server: &MockServer) -> Vec<bool> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|request| request.headers.contains_key("If-None-Match"))
+        .collect()
+}
+
+#[tokio::test]
+async fn not_modified_returns_cached_data() {
+    let server = MockServer::start().await;
+    mount_etag(&server).await;
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::memory())
+        .build()
+        .unwrap();
+    let first = client.get::<Value>("lyric").await.unwrap();
+    let second = client.get::<Value>("lyric").await.unwrap();
+
+    assert_eq!(first, json!({"title": "Roodkapje"}));
+    assert_eq!(second, first);
+    assert_eq!(conditional_requests(&server).await, [false, true]);
+}
+
+#[tokio::test]
+async fn fresh_response_is_served_without_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "max-age=60")
+                .set_body_json(json!([1, 2, 3])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::memory())
+        .build()
+        .unwrap();
+    for _ in 0..3 {
+        assert_eq!(
+            client.get::<Value>("lyric").await.unwrap(),
+            json!([1, 2, 3])
+        );
+    }
+}
+
+#[tokio::test]
+async fn no_store_and_writes_are_not_cached() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "no-store")
+                .insert_header("ETag", r#""v1""#)
+                .set_body_json(json!({})),
+        )
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::memory())
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    assert_eq!(conditional_requests(&server).await, [false, false]);
+}
+
+#[tokio::test]
+async fn successful_write_invalidates_cached_response() {
+    let server = MockServer::start().await;
+    mount_etag(&server).await;
+    Mock::given(method("PUT"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .mount(&server)
+        .await;
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::memory())
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    client.put::<_, Value>("lyric", &json!({})).await.unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+
+    assert_eq!(conditional_requests(&server).await, [false, false, false]);
+}
+
+#[tokio::test]
+async fn disk_store_survives_the_client() {
+    let server = MockServer::start().await;
+    mount_etag(&server).await;
+    let directory =
+        std::env::temp_dir().join(format!("rest-json-client-cache-{}", std::process::id()));
+
+    common::builder(&server.uri())
+        .middleware(ResponseCache::disk(&directory))
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap();
+    let cached = common::builder(&server.uri())
+        .middleware(ResponseCache::disk(&directory))
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap();
+
+    assert_eq!(cached, json!({"title": "Roodkapje"}));
+    assert_eq!(conditional_requests(&server).await, [false, true]);
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+async fn mount_fresh(server: &MockServer, vary: &str) {
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "max-age=60")
+                .insert_header("Vary", vary)
+                .set_body_json(json!([1, 2, 3])),
+        )
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn api_keys_do_not_share_cached_responses() {
+    let server = MockServer::start().await;
+    mount_fresh(&server, "Accept-Encoding").await;
+    let cache = ResponseCache::memory();
+    let with_key = |key: &str| {
+        common::builder(&server.uri())
+            .middleware(cache.clone())
+            .authentication(Authentication::new_api_key(
+                ApiKeyLocation::Header,
+                "X-Api-Key",
+                key,
+            ))
+            .build()
+            .unwrap()
+    };
+
+    for key in ["alice", "bob", "alice"] {
+        with_key(key).get::<Value>("lyric").await.unwrap();
+    }
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn vary_selects_the_cached_response() {
+    let server = MockServer::start().await;
+    mount_fresh(&server, "Accept-Language").await;
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::memory())
+        .build()
+        .unwrap();
+    for language in ["nl", "nl", "en"] {
+        client
+            .request(Method::GET, "lyric")
+            .header("Accept-Language", language)
+            .send_json::<Value>()
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn vary_star_is_not_cached() {
+    let server = MockServer::start().await;
+    mount_fresh(&server, "*").await;
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::memory())
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn memory_store_forgets_least_recently_used() {
+    let server = MockServer::start().await;
+    mount_fresh(&server, "Accept-Encoding").await;
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::new(MemoryStore::with_capacity(2)))
+        .build()
+        .unwrap();
+    for uri in ["lyric", "playlist", "lyric", "sheet", "lyric", "playlist"] {
+        client.get::<Value>(uri).await.unwrap();
+    }
+
+    let paths = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|request| request.url.path().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["/lyric", "/playlist", "/sheet", "/playlist"]);
+}
+
+#[tokio::test]
+async fn ranges_and_other_content_types_are_not_cached() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/sheet.pdf"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "max-age=60")
+                .set_body_raw("%PDF-1.7", "application/pdf"),
+        )
+        .mount(&server)
+        .await;
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::memory())
+        .build()
+        .unwrap();
+    for _ in 0..2 {
+        client
+            .request(Method::GET, "sheet.pdf")
+            .send_bytes()
+            .await
+            .unwrap();
+    }
+    mount_fresh(&server, "Accept-Encoding").await;
+    for _ in 0..2 {
+        client
+            .request(Method::GET, "lyric")
+            .header("Range", "bytes=0-")
+            .send_json::<Value>()
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn signed_requests_bypass_the_cache() {
+    let server = MockServer::start().await;
+    mount_fresh(&server, "Accept-Encoding").await;
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::memory())
+        .authentication(Authentication::new_hmac(HmacSigner::new(b"shared secret")))
+        .build()
+        .unwrap();
+
+    client.get::<Value>("lyric").await.unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn disk_store_removes_oldest_files() {
+    let server = MockServer::start().await;
+    mount_fresh(&server, "Accept-Encoding").await;
+    let directory =
+        std::env::temp_dir().join(format!("rest-json-client-evict-{}", std::process::id()));
+
+    let client = common::builder(&server.uri())
+        .middleware(ResponseCache::new(DiskStore::with_capacity(&directory, 2)))
+        .build()
+        .unwrap();
+    for uri in ["lyric", "playlist", "sheet"] {
+        client.get::<Value>(uri).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let files = std::fs::read_dir(&directory).unwrap().count();
+    client.get::<Value>("lyric").await.unwrap();
+    std::fs::remove_dir_all(directory).unwrap();
+
+    assert_eq!(files, 2);
+    assert_eq!(server.received_requests().await.unwrap().len(), 4);
+}
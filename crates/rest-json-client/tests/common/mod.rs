@@ -0,0 +1,18 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use rest_json_client::{ApiClient, ApiClientBuilder};
+use std::time::Duration;
+use wiremock::MockServer;
+
+/// A builder for a client of the server at `uri`, with a timeout so a test fails instead of hanging
+pub fn builder(uri: &str) -> ApiClientBuilder {
+    let mut builder = ApiClientBuilder::new(uri);
+    builder.timeout(Duration::from_secs(10));
+    builder
+}
+
+/// A client of the mock server
+pub fn client(server: &MockServer) -> ApiClient {
+    builder(&server.uri()).build().unwrap()
+}
@@ -0,0 +1,79 @@
+use rest_json_client::{ApiClientBuilder, Authentication, Error};
+use serde_json::Value;
+use std::path::PathBuf;
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rest-json-client-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+async fn expect_authorization(authentication: Authentication, authorization: &str) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("Authorization", authorization))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    ApiClientBuilder::new(&server.uri())
+        .authentication(authentication)
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn netrc_entry_for_host_is_used() {
+    let path = temp_file(
+        "netrc",
+        "machine example.com login other password wrong\n\
+         macdef init\n\
+         machine www.paulmin.nl login fake password fake\n\
+         \n\
+         machine www.paulmin.nl\n  login paul\n  password s3cr3t\n\
+         default login anonymous password guest\n",
+    );
+    let authentication = Authentication::from_netrc_file(&path, "www.paulmin.nl").unwrap();
+    // paul:s3cr3t
+    expect_authorization(authentication, "Basic cGF1bDpzM2NyM3Q=").await;
+
+    let authentication = Authentication::from_netrc_file(&path, "unknown.org").unwrap();
+    // anonymous:guest
+    expect_authorization(authentication, "Basic YW5vbnltb3VzOmd1ZXN0").await;
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn token_is_read_from_credentials_file() {
+    let path = temp_file("token.json", r#"{"token": "s3cr3t"}"#);
+    let authentication = Authentication::from_file(&path).unwrap();
+    expect_authorization(authentication, "Bearer s3cr3t").await;
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn missing_credentials_are_an_error() {
+    assert!(matches!(
+        Authentication::from_env("REST_JSON_CLIENT_MISSING"),
+        Err(Error::Auth(_))
+    ));
+    assert!(matches!(
+        Authentication::from_file("/nonexistent/credentials.json"),
+        Err(Error::Auth(_))
+    ));
+    let path = temp_file(
+        "empty-netrc",
+        "machine example.com login paul password s3cr3t\n",
+    );
+    assert!(matches!(
+        Authentication::from_netrc_file(&path, "www.paulmin.nl"),
+        Err(Error::Auth(_))
+    ));
+    std::fs::remove_file(path).unwrap();
+}
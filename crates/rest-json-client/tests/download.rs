@@ -0,0 +1,184 @@
+mod common;
+
+use rest_json_client::{Download, Error, Progress};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SHEET: &[u8] = b"%PDF-1.7 roodkapje";
+
+/// Answer a connection with each of the responses in turn and return the requests received
+async fn serve(responses: Vec<Vec<u8>>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let uri = format!("http://{}/", listener.local_addr().unwrap());
+    let requests = tokio::spawn(async move {
+        let mut requests = vec![];
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            while !request.ends_with(b"\r\n\r\n") {
+                let mut byte = [0];
+                socket.read_exact(&mut byte).await.unwrap();
+                request.push(byte[0]);
+            }
+            requests.push(String::from_utf8(request).unwrap().to_lowercase());
+            socket.write_all(&response).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+        requests
+    });
+    (uri, requests)
+}
+
+fn response(status: &str, headers: &str, body: &[u8]) -> Vec<u8> {
+    let mut response =
+        format!("HTTP/1.1 {status}\r\nConnection: close\r\n{headers}\r\n").into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+#[tokio::test]
+async fn body_is_written_with_progress() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("Accept-Encoding", "identity"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(SHEET))
+        .mount(&server)
+        .await;
+
+    let reported = Arc::new(Mutex::new(vec![]));
+    let progress = reported.clone();
+    let mut file = vec![];
+    let written = common::client(&server)
+        .download_with(
+            "sheet.pdf",
+            &mut file,
+            Download::new().progress(move |p| progress.lock().unwrap().push(p)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(file, SHEET);
+    assert_eq!(written, SHEET.len() as u64);
+    let total = Some(SHEET.len() as u64);
+    assert_eq!(
+        reported.lock().unwrap().last(),
+        Some(&Progress {
+            received: written,
+            total
+        })
+    );
+}
+
+#[tokio::test]
+async fn partial_download_is_continued_with_range() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("Range", "bytes=9-"))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header("Content-Range", "bytes 9-17/18")
+                .set_body_bytes(&SHEET[9..]),
+        )
+        .mount(&server)
+        .await;
+
+    let mut file = SHEET[..9].to_vec();
+    let written = common::client(&server)
+        .download_with("sheet.pdf", &mut file, Download::new().resume_from(9))
+        .await
+        .unwrap();
+
+    assert_eq!(file, SHEET);
+    assert_eq!(written, 18);
+}
+
+#[tokio::test]
+async fn ignored_range_skips_bytes_written_before() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(SHEET))
+        .mount(&server)
+        .await;
+
+    let mut file = SHEET[..9].to_vec();
+    common::client(&server)
+        .download_with("sheet.pdf", &mut file, Download::new().resume_from(9))
+        .await
+        .unwrap();
+
+    assert_eq!(file, SHEET);
+}
+
+#[tokio::test]
+async fn completed_download_is_not_repeated() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(416).insert_header("Content-Range", "bytes */18"))
+        .mount(&server)
+        .await;
+
+    let mut file = SHEET.to_vec();
+    let written = common::client(&server)
+        .download_with("sheet.pdf", &mut file, Download::new().resume_from(18))
+        .await
+        .unwrap();
+
+    assert_eq!(written, 18);
+    assert_eq!(file, SHEET);
+}
+
+#[tokio::test]
+async fn interrupted_download_is_resumed() {
+    let (uri, requests) = serve(vec![
+        response(
+            "200 OK",
+            "Content-Length: 18\r\nETag: \"v1\"\r\n",
+            &SHEET[..5],
+        ),
+        response(
+            "206 Partial Content",
+            "Content-Length: 13\r\nContent-Range: bytes 5-17/18\r\n",
+            &SHEET[5..],
+        ),
+    ])
+    .await;
+
+    let mut file = vec![];
+    common::builder(&uri)
+        .build()
+        .unwrap()
+        .download("sheet.pdf", &mut file)
+        .await
+        .unwrap();
+
+    assert_eq!(file, SHEET);
+    let requests = requests.await.unwrap();
+    assert!(requests[1].contains("range: bytes=5-\r\n"));
+    assert!(requests[1].contains("if-range: \"v1\"\r\n"));
+}
+
+#[tokio::test]
+async fn truncated_download_is_an_error() {
+    let (uri, _) = serve(vec![response(
+        "200 OK",
+        "Content-Length: 18\r\n",
+        &SHEET[..5],
+    )])
+    .await;
+
+    // buffered, so the bytes only reach the file when the download flushes after the error
+    let mut file = BufWriter::new(vec![]);
+    let error = common::builder(&uri)
+        .build()
+        .unwrap()
+        .download_with("sheet.pdf", &mut file, Download::new().max_resumes(0))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Transport(_) | Error::Io(_)));
+    assert_eq!(file.get_ref()[..], SHEET[..5]);
+}
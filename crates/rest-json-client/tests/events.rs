@@ -0,0 +1,212 @@
+mod common;
+
+use futures_util::{StreamExt, TryStreamExt};
+use rest_json_client::{Authentication, Error, Event};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Change {
+    id: u32,
+}
+
+fn event_stream(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "text/event-stream")
+}
+
+#[tokio::test]
+async fn events_are_parsed() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("Accept", "text/event-stream"))
+        .respond_with(event_stream(concat!(
+            ": keep alive\r\n",
+            "retry: 10000\r\n",
+            "event: lyric\r\n",
+            "id: 7\r\n",
+            "data: {\"id\":\r\n",
+            "data:  1}\r\n",
+            "\r\n",
+            "data: {\"id\": 2}\n",
+            "\n",
+        )))
+        .mount(&server)
+        .await;
+
+    let events = common::client(&server)
+        .events::<Change>("events")
+        .take(2)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        events,
+        [
+            Event {
+                id: Some("7".to_owned()),
+                event: "lyric".to_owned(),
+                data: Change { id: 1 },
+            },
+            Event {
+                id: Some("7".to_owned()),
+                event: "message".to_owned(),
+                data: Change { id: 2 },
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn reconnect_sends_last_event_id_and_credentials() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("Last-Event-ID", "1"))
+        .and(header("Authorization", "Bearer s3cr3t"))
+        .respond_with(event_stream("id: 2\ndata: {\"id\": 2}\n\n"))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(header("Authorization", "Bearer s3cr3t"))
+        .respond_with(event_stream(
+            "retry: 1\nid: 1\ndata: {\"id\": 1}\n\ndata: {\"id\": 9",
+        ))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+
+    let events = common::builder(&server.uri())
+        .authentication(Authentication::new_bearer("s3cr3t"))
+        .build()
+        .unwrap()
+        .events::<Change>("events")
+        .take(2)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    let ids = events.iter().map(|event| event.data.id).collect::<Vec<_>>();
+    assert_eq!(ids, [1, 2]);
+    assert_eq!(events[1].id.as_deref(), Some("2"));
+}
+
+#[tokio::test]
+async fn no_content_ends_the_stream() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = common::client(&server)
+        .events::<Change>("events")
+        .collect::<Vec<_>>()
+        .await;
+
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn error_status_ends_the_stream() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(403))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = common::client(&server)
+        .events::<Change>("events")
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], Err(Error::Status(error)) if error.status() == 403));
+}
+
+#[tokio::test]
+async fn invalid_data_does_not_end_the_stream() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(event_stream("data: not json\n\ndata: {\"id\": 3}\n\n"))
+        .mount(&server)
+        .await;
+
+    let events = common::client(&server)
+        .events::<Change>("events")
+        .take(2)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert!(matches!(events[0], Err(Error::Deserialize { .. })));
+    assert_eq!(events[1].as_ref().unwrap().data, Change { id: 3 });
+}
+
+#[tokio::test]
+async fn other_content_type_ends_the_stream() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"id": 1})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = common::client(&server)
+        .events::<Change>("events")
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], Err(Error::Io(_))), "{events:?}");
+}
+
+#[tokio::test]
+async fn unreachable_server_ends_the_stream() {
+    let client = common::builder("http://127.0.0.1:1/").build().unwrap();
+
+    let events = client.events::<Change>("events").collect::<Vec<_>>().await;
+
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], Err(Error::Transport(_))), "{events:?}");
+}
+
+#[tokio::test]
+async fn failed_reconnects_end_the_stream() {
+    // answers a single connection, so that every reconnect is refused
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let uri = format!("http://{}/", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = vec![];
+        while !request.ends_with(b"\r\n\r\n") {
+            let mut byte = [0];
+            socket.read_exact(&mut byte).await.unwrap();
+            request.push(byte[0]);
+        }
+        let body = "retry: 10\ndata: {\"id\": 1}\n\n";
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+    });
+
+    let client = common::builder(&uri).build().unwrap();
+    let events = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.events::<Change>("events").collect::<Vec<_>>(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].as_ref().unwrap().data, Change { id: 1 });
+    assert!(matches!(&events[1], Err(Error::Transport(_))), "{events:?}");
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use futures_util::{StreamExt, TryStreamExt};
+use rest_json_client::{ApiClient, Error};
+use serde_json::{Value, json};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn client(body: &str) -> (MockServer, ApiClient) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+    let client = common::client(&server);
+    (server, client)
+}
+
+async fn write_chunk(socket: &mut TcpStream, chunk: &str) {
+    let chunk = format!("{:x}\r\n{chunk}\r\n", chunk.len());
+    socket.write_all(chunk.as_bytes()).await.unwrap();
+    socket.flush().await.unwrap();
+}
+
+/// Serve a single chunked response, sending the chunks in `after` only when `gate` is opened
+async fn serve_chunked(
+    before: &'static [&'static str],
+    gate: oneshot::Receiver<()>,
+    after: &'static [&'static str],
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0; 4096];
+        let _ = socket.read(&mut request).await.unwrap();
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n")
+            .await
+            .unwrap();
+        for chunk in before {
+            write_chunk(&mut socket, chunk).await;
+        }
+        gate.await.unwrap();
+        for chunk in after {
+            write_chunk(&mut socket, chunk).await;
+        }
+        socket.write_all(b"0\r\n\r\n").await.unwrap();
+    });
+    format!("http://{address}/")
+}
+
+#[tokio::test]
+async fn items_arrive_before_the_body_is_complete() {
+    let (open, gate) = oneshot::channel();
+    let uri = serve_chunked(&["[1, 2"], gate, &["3, 4", "]"]).await;
+    let client = common::builder(&uri).build().unwrap();
+    let mut items = Box::pin(client.get_stream::<u32>("numbers"));
+
+    assert_eq!(items.next().await.unwrap().unwrap(), 1);
+    open.send(()).unwrap();
+    assert_eq!(items.try_collect::<Vec<_>>().await.unwrap(), [23, 4]);
+}
+
+#[tokio::test]
+async fn newline_delimited_json_is_parsed() {
+    let (_server, client) = client("{\"id\": 1}\n\n{\"id\": 2}\n3").await;
+    let items = client
+        .get_stream::<Value>("lyric")
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(items, [json!({"id": 1}), json!({"id": 2}), json!(3)]);
+}
+
+#[tokio::test]
+async fn empty_array_has_no_items() {
+    let (_server, client) = client(" [ ] ").await;
+    let items = client
+        .get_stream::<Value>("lyric")
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert!(items.is_empty());
+}
+
+#[tokio::test]
+async fn invalid_item_ends_the_stream() {
+    let (_server, client) = client(r#"[1, "two", 3]"#).await;
+    let results = client.get_stream::<u32>("lyric").collect::<Vec<_>>().await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(*results[0].as_ref().unwrap(), 1);
+    assert!(matches!(results[1], Err(Error::Deserialize { .. })));
+}
+
+#[tokio::test]
+async fn unterminated_array_is_an_error() {
+    let (_server, client) = client("[1, 2").await;
+    let results = client.get_stream::<u32>("lyric").collect::<Vec<_>>().await;
+
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[2], Err(Error::Deserialize { .. })));
+}
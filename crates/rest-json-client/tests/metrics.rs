@@ -0,0 +1,161 @@
+#![cfg(feature = "metrics")]
+
+use metrics::{SharedString, Unit};
+use metrics_util::{
+    CompositeKey,
+    debugging::{DebugValue, DebuggingRecorder},
+};
+use rest_json_client::{ApiClientBuilder, Method, RetryPolicy};
+use serde_json::Value;
+use std::{future::Future, time::Duration};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+type Snapshot = Vec<(CompositeKey, Option<Unit>, Option<SharedString>, DebugValue)>;
+
+/// Run `future` with a recorder that is local to the test and return what was recorded
+fn recorded<F: Future>(future: F) -> Snapshot {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    metrics::with_local_recorder(&recorder, || runtime.block_on(future));
+    snapshotter.snapshot().into_vec()
+}
+
+/// The value of the metric with the given name and labels
+fn metric<'a>(
+    snapshot: &'a Snapshot,
+    name: &str,
+    labels: &[(&str, &str)],
+) -> Option<&'a DebugValue> {
+    snapshot
+        .iter()
+        .find(|(key, ..)| {
+            let key = key.key();
+            key.name() == name
+                && labels.iter().all(|(label, value)| {
+                    key.labels()
+                        .any(|l| l.key() == *label && l.value() == *value)
+                })
+        })
+        .map(|(.., value)| value)
+}
+
+#[test]
+fn request_is_counted_by_route_and_status_class() {
+    let snapshot = recorded(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .mount(&server)
+            .await;
+
+        let client = ApiClientBuilder::new(&server.uri())
+            .retry_policy(RetryPolicy::new(2).initial_backoff(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+        client
+            .request(Method::GET, "lyric/42?full=true")
+            .route("/lyric/{id}")
+            .send_json::<Value>()
+            .await
+            .unwrap();
+    });
+
+    let labels = [
+        ("method", "GET"),
+        ("route", "lyric/{id}"),
+        ("status_class", "2xx"),
+        ("error_kind", "none"),
+    ];
+    assert_eq!(
+        metric(&snapshot, "http_client_requests_total", &labels),
+        Some(&DebugValue::Counter(1))
+    );
+    assert!(matches!(
+        metric(&snapshot, "http_client_request_duration_seconds", &labels),
+        Some(DebugValue::Histogram(values)) if values.len() == 1
+    ));
+    assert_eq!(
+        metric(
+            &snapshot,
+            "http_client_retries_total",
+            &[("route", "lyric/{id}")]
+        ),
+        Some(&DebugValue::Counter(1))
+    );
+}
+
+#[test]
+fn failed_request_is_counted_by_error_kind() {
+    let snapshot = recorded(async {
+        let client = ApiClientBuilder::new("http://127.0.0.1:1/")
+            .build()
+            .unwrap();
+        client.get::<Value>("lyric").await.unwrap_err();
+    });
+
+    assert_eq!(
+        metric(
+            &snapshot,
+            "http_client_requests_total",
+            &[
+                ("route", "unknown"),
+                ("status_class", "none"),
+                ("error_kind", "transport")
+            ]
+        ),
+        Some(&DebugValue::Counter(1))
+    );
+}
+
+#[test]
+fn unreadable_response_is_counted_by_error_kind() {
+    let snapshot = recorded(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/lyric/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/lyric/2"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = ApiClientBuilder::new(&server.uri()).build().unwrap();
+        for id in ["1", "2"] {
+            client
+                .request(Method::GET, &format!("lyric/{id}"))
+                .route("lyric/{id}")
+                .send_json::<Value>()
+                .await
+                .unwrap_err();
+        }
+    });
+
+    for (status_class, error_kind) in [("2xx", "deserialize"), ("4xx", "status")] {
+        assert_eq!(
+            metric(
+                &snapshot,
+                "http_client_requests_total",
+                &[
+                    ("route", "lyric/{id}"),
+                    ("status_class", status_class),
+                    ("error_kind", error_kind)
+                ]
+            ),
+            Some(&DebugValue::Counter(1)),
+            "{status_class} {error_kind}"
+        );
+    }
+}
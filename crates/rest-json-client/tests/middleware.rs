@@ -0,0 +1,127 @@
+use futures_util::{FutureExt, future::BoxFuture};
+use rest_json_client::{
+    ApiClientBuilder, Error, Middleware, Next, Request, Response, Result, header::HeaderValue,
+};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use wiremock::matchers::{headers, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Records its name before and after the rest of the stack and tags the request
+struct Layer {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Middleware for Layer {
+    fn handle<'a>(
+        &'a self,
+        mut request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        async move {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} request", self.name));
+            request
+                .headers_mut()
+                .append("X-Layer", HeaderValue::from_static(self.name));
+            let result = next.run(request).await;
+            let outcome = match &result {
+                Ok(response) => response.status().as_str().to_owned(),
+                Err(_) => "error".to_owned(),
+            };
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} response {outcome}", self.name));
+            result
+        }
+        .boxed()
+    }
+}
+
+/// Answers every request itself
+struct Stub;
+
+impl Middleware for Stub {
+    fn handle<'a>(&'a self, _: Request, _: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        let response = http::Response::builder()
+            .status(200)
+            .body(json!({"stubbed": true}).to_string())
+            .unwrap();
+        futures_util::future::ok(Response::from(response)).boxed()
+    }
+}
+
+#[tokio::test]
+async fn layers_run_in_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(headers("X-Layer", vec!["outer", "inner"]))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let log = Arc::new(Mutex::new(vec![]));
+    let client = ApiClientBuilder::new(&server.uri())
+        .middleware(Layer {
+            name: "outer",
+            log: log.clone(),
+        })
+        .middleware(Layer {
+            name: "inner",
+            log: log.clone(),
+        })
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "outer request",
+            "inner request",
+            "inner response 200",
+            "outer response 200"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn layer_can_short_circuit() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = ApiClientBuilder::new(&server.uri())
+        .middleware(Stub)
+        .build()
+        .unwrap();
+    let value = client.get::<Value>("lyric").await.unwrap();
+    assert_eq!(value, json!({"stubbed": true}));
+}
+
+#[tokio::test]
+async fn layer_observes_errors() {
+    let log = Arc::new(Mutex::new(vec![]));
+    let client = ApiClientBuilder::new("http://127.0.0.1:1/")
+        .middleware(Layer {
+            name: "outer",
+            log: log.clone(),
+        })
+        .build()
+        .unwrap();
+    let error = client.get::<Value>("lyric").await.unwrap_err();
+
+    assert!(matches!(error, Error::Transport(_)));
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["outer request", "outer response error"]
+    );
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use futures_util::stream;
+use rest_json_client::{ApiClient, Error, Multipart};
+use serde_json::{Value, json};
+use std::convert::Infallible;
+use wiremock::matchers::{header_regex, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn server() -> (MockServer, ApiClient) {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(header_regex(
+            "Content-Type",
+            "^multipart/form-data; boundary=",
+        ))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({"id": 1})))
+        .mount(&server)
+        .await;
+    let client = common::client(&server);
+    (server, client)
+}
+
+async fn received_body(server: &MockServer) -> String {
+    let requests = server.received_requests().await.unwrap();
+    String::from_utf8(requests[0].body.clone()).unwrap()
+}
+
+#[tokio::test]
+async fn text_json_and_byte_parts_are_sent() {
+    let (server, client) = server().await;
+    let chunks = stream::iter([Ok::<_, Infallible>("Daar was "), Ok("laatst")]);
+    let form = Multipart::new()
+        .text("title", "Roodkapje")
+        .json("lyric", &json!({"parts": []}))
+        .bytes("cover", b"png".as_slice(), "cover.png", "image/png")
+        .stream("text", chunks, "lyric.txt", "text/plain");
+
+    let created = client
+        .post_multipart::<Value>("attachment", form)
+        .await
+        .unwrap();
+
+    assert_eq!(created, json!({"id": 1}));
+    let body = received_body(&server).await;
+    for expected in [
+        "name=\"title\"\r\n\r\nRoodkapje\r\n",
+        "name=\"lyric\"\r\nContent-Type: application/json\r\n\r\n{\"parts\":[]}\r\n",
+        "name=\"cover\"; filename=\"cover.png\"\r\nContent-Type: image/png\r\n\r\npng\r\n",
+        "name=\"text\"; filename=\"lyric.txt\"\r\nContent-Type: text/plain\r\n\r\nDaar was laatst\r\n",
+    ] {
+        assert!(body.contains(expected), "{expected:?} not in {body:?}");
+    }
+}
+
+#[tokio::test]
+async fn file_part_is_named_after_the_file() {
+    let (server, client) = server().await;
+    let path = std::env::temp_dir().join(format!("roodkapje-{}.pdf", std::process::id()));
+    std::fs::write(&path, "%PDF-1.7").unwrap();
+
+    client
+        .post_multipart::<Value>(
+            "attachment",
+            Multipart::new().file("sheet", &path, "application/pdf"),
+        )
+        .await
+        .unwrap();
+
+    std::fs::remove_file(&path).unwrap();
+    let expected = format!(
+        "name=\"sheet\"; filename=\"{}\"\r\nContent-Type: application/pdf\r\n\r\n%PDF-1.7\r\n",
+        path.file_name().unwrap().to_string_lossy()
+    );
+    assert!(received_body(&server).await.contains(&expected));
+}
+
+#[tokio::test]
+async fn missing_file_is_reported_without_sending() {
+    let (server, client) = server().await;
+    let form = Multipart::new().text("title", "Roodkapje").file(
+        "sheet",
+        "does/not/exist.pdf",
+        "application/pdf",
+    );
+
+    let error = client
+        .post_multipart::<Value>("attachment", form)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Io(_)));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use rest_json_client::{Authentication, Error, OAuth2Config, OAuth2TokenProvider};
+use serde_json::{Value, json};
+use std::{sync::Arc, time::Duration};
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn config(server: &MockServer) -> OAuth2Config {
+    OAuth2Config::new(&format!("{}/oauth2/token", server.uri()), "lipl")
+}
+
+async fn api(server: &MockServer, token: &str) {
+    Mock::given(method("GET"))
+        .and(path("/lyric"))
+        .and(header("Authorization", format!("Bearer {token}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(server)
+        .await;
+}
+
+async fn token_requests(server: &MockServer) -> Vec<String> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|request| request.url.path() == "/oauth2/token")
+        .map(|request| String::from_utf8(request.body).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn client_credentials_token_is_obtained_once() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        // lipl:secret
+        .and(header("Authorization", "Basic bGlwbDpzZWNyZXQ="))
+        .and(body_string_contains("grant_type=client_credentials"))
+        .and(body_string_contains("scope=lyrics%3Aread+playlists%3Aread"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "access-1",
+            "token_type": "Bearer",
+            "expires_in": 3600
+        })))
+        .mount(&server)
+        .await;
+    api(&server, "access-1").await;
+
+    let config = config(&server)
+        .client_secret("secret")
+        .scope("lyrics:read")
+        .scope("playlists:read");
+    let client = common::builder(&server.uri())
+        .authentication(Authentication::new_client_credentials(config).unwrap())
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+
+    assert_eq!(token_requests(&server).await.len(), 1);
+}
+
+#[tokio::test]
+async fn refresh_token_is_rotated() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .and(body_string_contains("grant_type=refresh_token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "access-2",
+            "token_type": "Bearer",
+            "expires_in": 0,
+            "refresh_token": "refresh-2"
+        })))
+        .mount(&server)
+        .await;
+    api(&server, "access-2").await;
+
+    let authentication = Authentication::new_refresh_token(config(&server), "refresh-1").unwrap();
+    let client = common::builder(&server.uri())
+        .authentication(authentication)
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+
+    let requests = token_requests(&server).await;
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].contains("refresh_token=refresh-1"));
+    assert!(requests[0].contains("client_id=lipl"));
+    assert!(requests[1].contains("refresh_token=refresh-2"));
+}
+
+#[tokio::test]
+async fn rejected_grant_is_authentication_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": "invalid_grant",
+            "error_description": "Refresh token expired"
+        })))
+        .mount(&server)
+        .await;
+
+    let authentication = Authentication::new_refresh_token(config(&server), "expired").unwrap();
+    let error = common::builder(&server.uri())
+        .authentication(authentication)
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap_err();
+
+    match error {
+        Error::Auth(message) => assert_eq!(
+            message,
+            "token request rejected with status 400 Bad Request: invalid_grant, Refresh token expired"
+        ),
+        other => panic!("Unexpected error {other}"),
+    }
+}
+
+#[tokio::test]
+async fn slow_token_endpoint_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"access_token": "access-1", "expires_in": 3600}))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = OAuth2TokenProvider::client_credentials(config(&server))
+        .unwrap()
+        .timeout(Duration::from_millis(100));
+    let authentication = Authentication::OAuth2(Arc::new(provider));
+    assert_eq!(format!("{authentication:?}"), "OAuth2");
+    let error = common::builder(&server.uri())
+        .authentication(authentication)
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Timeout(_)), "{error:?}");
+}
+
+#[tokio::test]
+async fn expires_in_may_be_a_string() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "access-1",
+            "expires_in": "3600"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    api(&server, "access-1").await;
+
+    let client = common::builder(&server.uri())
+        .authentication(Authentication::new_client_credentials(config(&server)).unwrap())
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+}
+
+#[tokio::test]
+async fn invalid_token_response_does_not_reveal_tokens() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/oauth2/token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "access_token": "leaked-access",
+            "refresh_token": "leaked-refresh",
+            "expires_in": true
+        })))
+        .mount(&server)
+        .await;
+
+    let error = common::builder(&server.uri())
+        .authentication(Authentication::new_refresh_token(config(&server), "refresh-1").unwrap())
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Auth(_)), "{error:?}");
+    for text in [error.to_string(), format!("{error:?}")] {
+        assert!(!text.contains("leaked"), "{text}");
+    }
+}
@@ -0,0 +1,209 @@
+mod common;
+
+use common::client;
+use futures_util::{StreamExt, TryStreamExt};
+use rest_json_client::{Error, Pagination};
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_page(server: &MockServer, name: &str, value: &str, body: serde_json::Value) {
+    Mock::given(method("GET"))
+        .and(query_param(name, value))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn page_and_limit_stop_at_short_page() {
+    let server = MockServer::start().await;
+    mount_page(&server, "_page", "1", json!([1, 2])).await;
+    mount_page(&server, "_page", "2", json!([3, 4])).await;
+    mount_page(&server, "_page", "3", json!([5])).await;
+
+    let items = client(&server)
+        .paginate::<u32>("posts", Pagination::page("_page", "_limit", 2))
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(items, [1, 2, 3, 4, 5]);
+    let requests = server.received_requests().await.unwrap();
+    assert!(
+        requests
+            .iter()
+            .all(|request| request.url.query().unwrap().contains("_limit=2"))
+    );
+}
+
+#[tokio::test]
+async fn offset_and_limit_stop_at_empty_page() {
+    let server = MockServer::start().await;
+    mount_page(&server, "offset", "0", json!([1, 2])).await;
+    mount_page(&server, "offset", "2", json!([])).await;
+
+    let items = client(&server)
+        .paginate::<u32>("posts", Pagination::offset("offset", "limit", 2))
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(items, [1, 2]);
+}
+
+#[tokio::test]
+async fn cursor_is_read_from_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param_is_missing("cursor"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"data": ["a", "b"], "meta": {"next": "c2"}})),
+        )
+        .mount(&server)
+        .await;
+    mount_page(
+        &server,
+        "cursor",
+        "c2",
+        json!({"data": ["c"], "meta": {"next": null}}),
+    )
+    .await;
+
+    let items = client(&server)
+        .paginate::<String>(
+            "lyric",
+            Pagination::cursor("cursor", "/meta/next").items("/data"),
+        )
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(items, ["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn link_header_is_followed() {
+    let server = MockServer::start().await;
+    let next = format!("{}/lyric?page=2", server.uri());
+    Mock::given(method("GET"))
+        .and(path("/lyric"))
+        .and(query_param_is_missing("page"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([1]))
+                .insert_header(
+                    "Link",
+                    format!(r#"<{next}>; rel="next", </lyric?page=9>; rel="last""#).as_str(),
+                ),
+        )
+        .mount(&server)
+        .await;
+    mount_page(&server, "page", "2", json!([2])).await;
+
+    let items = client(&server)
+        .paginate::<u32>("lyric", Pagination::link())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(items, [1, 2]);
+}
+
+#[tokio::test]
+async fn max_pages_ends_endless_collection() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"items": [0], "next": "again"})),
+        )
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let items = client(&server)
+        .paginate::<u32>(
+            "lyric",
+            Pagination::cursor("cursor", "/next")
+                .items("/items")
+                .max_pages(3),
+        )
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(items.len(), 3);
+}
+
+#[tokio::test]
+async fn error_ends_the_stream() {
+    let server = MockServer::start().await;
+    mount_page(&server, "_page", "1", json!([1])).await;
+    Mock::given(method("GET"))
+        .and(query_param("_page", "2"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let results = client(&server)
+        .paginate::<u32>("posts", Pagination::page("_page", "_limit", 1))
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(*results[0].as_ref().unwrap(), 1);
+    assert!(matches!(results[1], Err(Error::Status(_))));
+}
+
+#[tokio::test]
+async fn link_to_other_server_is_not_followed() {
+    let server = MockServer::start().await;
+    let other = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([1]))
+                .insert_header(
+                    "Link",
+                    format!(r#"<{}/steal>; rel="next""#, other.uri()).as_str(),
+                ),
+        )
+        .mount(&server)
+        .await;
+
+    let results = client(&server)
+        .paginate::<u32>("lyric", Pagination::link())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(matches!(results[1], Err(Error::Url(_))));
+    assert!(other.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn link_outside_of_prefix_is_not_followed() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/lyric"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([1]))
+                .insert_header("Link", r#"</admin/users?page=2>; rel="next""#),
+        )
+        .mount(&server)
+        .await;
+
+    let results = common::builder(&format!("{}/api/v1/", server.uri()))
+        .build()
+        .unwrap()
+        .paginate::<u32>("lyric", Pagination::link())
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(matches!(results[1], Err(Error::Url(_))));
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
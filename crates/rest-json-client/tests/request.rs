@@ -0,0 +1,85 @@
+mod common;
+
+use common::client;
+use rest_json_client::{Error, Method};
+use serde_json::{Value, json};
+use std::time::Duration;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn query_and_header_are_sent() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/posts"))
+        .and(query_param("userId", "1"))
+        .and(header("X-Request-Id", "abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": 1}])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let posts = client(&server)
+        .request(Method::GET, "posts")
+        .query(&[("userId", 1)])
+        .header("X-Request-Id", "abc")
+        .send_json::<Value>()
+        .await
+        .unwrap();
+
+    assert_eq!(posts, json!([{"id": 1}]));
+}
+
+#[tokio::test]
+async fn send_text_and_send_bytes_return_the_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/lyric.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Daar was laatst een meisje loos"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/sheet.pdf"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(b"%PDF-1.7".to_vec(), "application/pdf"),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let text = client
+        .request(Method::GET, "lyric.txt")
+        .send_text()
+        .await
+        .unwrap();
+    let bytes = client
+        .request(Method::GET, "sheet.pdf")
+        .send_bytes()
+        .await
+        .unwrap();
+
+    assert_eq!(text, "Daar was laatst een meisje loos");
+    assert_eq!(&bytes[..], b"%PDF-1.7");
+}
+
+#[tokio::test]
+async fn request_timeout_overrides_client_timeout() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("{}")
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let error = client(&server)
+        .request(Method::GET, "slow")
+        .timeout(Duration::from_millis(100))
+        .send_json::<Value>()
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Timeout(_)), "{error}");
+}
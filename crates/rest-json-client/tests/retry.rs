@@ -0,0 +1,145 @@
+mod common;
+
+use rest_json_client::{Method, RetryPolicy, StatusCode};
+use std::time::Duration;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn unavailable_twice(server: &MockServer, verb: &str) {
+    Mock::given(method(verb))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(server)
+        .await;
+    Mock::given(method(verb))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn get_is_retried_until_success() {
+    let server = MockServer::start().await;
+    unavailable_twice(&server, "GET").await;
+
+    common::builder(&server.uri())
+        .retry_policy(RetryPolicy::new(3).initial_backoff(Duration::from_millis(1)))
+        .build()
+        .unwrap()
+        .get::<serde_json::Value>("item")
+        .await
+        .unwrap();
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let server = MockServer::start().await;
+    unavailable_twice(&server, "DELETE").await;
+
+    let result = common::builder(&server.uri())
+        .retry_policy(RetryPolicy::new(2).initial_backoff(Duration::from_millis(1)))
+        .build()
+        .unwrap()
+        .delete("item")
+        .await;
+
+    assert_eq!(
+        result.err().and_then(|e| e.status()),
+        Some(StatusCode::SERVICE_UNAVAILABLE)
+    );
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn post_is_not_retried_by_default() {
+    let server = MockServer::start().await;
+    unavailable_twice(&server, "POST").await;
+
+    let result = common::builder(&server.uri())
+        .retry_policy(RetryPolicy::new(3).initial_backoff(Duration::from_millis(1)))
+        .build()
+        .unwrap()
+        .post::<_, serde_json::Value>("item", ())
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn post_is_retried_when_enabled() {
+    let server = MockServer::start().await;
+    unavailable_twice(&server, "POST").await;
+
+    common::builder(&server.uri())
+        .retry_policy(
+            RetryPolicy::new(3)
+                .retry_post(true)
+                .initial_backoff(Duration::from_millis(1)),
+        )
+        .build()
+        .unwrap()
+        .post::<_, serde_json::Value>("item", ())
+        .await
+        .unwrap();
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn post_with_idempotency_key_is_retried() {
+    let server = MockServer::start().await;
+    unavailable_twice(&server, "POST").await;
+
+    common::builder(&server.uri())
+        .retry_policy(
+            RetryPolicy::new(3)
+                .retry_post(false)
+                .initial_backoff(Duration::from_millis(1)),
+        )
+        .build()
+        .unwrap()
+        .request(Method::POST, "item")
+        .idempotency_key("4f1c")
+        .json(&())
+        .send_json::<serde_json::Value>()
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    assert!(
+        requests
+            .iter()
+            .all(|request| request.headers["Idempotency-Key"] == "4f1c")
+    );
+}
+
+#[tokio::test]
+async fn retry_after_beyond_max_backoff_is_not_waited_for() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+        .mount(&server)
+        .await;
+
+    let result = common::builder(&server.uri())
+        .retry_policy(
+            RetryPolicy::new(3)
+                .max_backoff(Duration::from_secs(1))
+                .initial_backoff(Duration::from_millis(1)),
+        )
+        .build()
+        .unwrap()
+        .get::<serde_json::Value>("item")
+        .await;
+
+    assert_eq!(
+        result.err().and_then(|e| e.status()),
+        Some(StatusCode::TOO_MANY_REQUESTS)
+    );
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
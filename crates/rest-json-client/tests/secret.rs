@@ -0,0 +1,22 @@
+use rest_json_client::{Authentication, HmacSigner, OAuth2Config};
+
+#[test]
+fn credentials_are_redacted_from_debug_output() {
+    let authentications = [
+        Authentication::new_basic("paul", "s3cr3t"),
+        Authentication::new_bearer("s3cr3t"),
+        Authentication::new_hmac(HmacSigner::new(b"s3cr3t")),
+    ];
+    for authentication in authentications {
+        assert!(!format!("{authentication:?}").contains("s3cr3t"));
+    }
+}
+
+#[test]
+fn client_secret_is_redacted_from_debug_output() {
+    let config =
+        OAuth2Config::new("https://auth.example.com/token", "lipl").client_secret("s3cr3t");
+    let debug = format!("{config:?}");
+    assert!(debug.contains("lipl"));
+    assert!(!debug.contains("s3cr3t"));
+}
@@ -0,0 +1,89 @@
+use rest_json_client::{
+    ApiClientBuilder, Authentication, Canonicalization, HmacSigner, header::HeaderName,
+};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use wiremock::{Match, Mock, MockServer, Request, ResponseTemplate, matchers::any};
+
+/// Accepts only requests with a valid signature
+struct Signed(HmacSigner);
+
+impl Match for Signed {
+    fn matches(&self, request: &Request) -> bool {
+        let path = match request.url.query() {
+            Some(query) => format!("{}?{query}", request.url.path()),
+            None => request.url.path().to_owned(),
+        };
+        self.0
+            .verify_request(&request.method, &path, &request.headers, &request.body)
+    }
+}
+
+async fn server(signer: HmacSigner) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(Signed(signer))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn requests_are_signed() {
+    let server = server(HmacSigner::new(b"secret")).await;
+    let client = ApiClientBuilder::new(&server.uri())
+        .authentication(Authentication::new_hmac(HmacSigner::new(b"secret")))
+        .build()
+        .unwrap();
+
+    client.get::<Value>("lyric?full=true").await.unwrap();
+    client
+        .post::<_, Value>("lyric", json!({"title": "Hallo"}))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn wrong_secret_is_rejected() {
+    let server = server(HmacSigner::new(b"secret")).await;
+    let result = ApiClientBuilder::new(&server.uri())
+        .authentication(Authentication::new_hmac(HmacSigner::new(b"guess")))
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await;
+
+    assert_eq!(
+        result.err().and_then(|e| e.status()).map(u16::from),
+        Some(401)
+    );
+}
+
+#[tokio::test]
+async fn custom_headers_and_canonicalization() {
+    let signer = || {
+        HmacSigner::new(b"secret")
+            .signature_header(HeaderName::from_static("x-signature"))
+            .timestamp_header(HeaderName::from_static("x-date"))
+            .canonicalization(Canonicalization::Custom(Arc::new(|input| {
+                format!("{}|{}|{}", input.timestamp, input.method, input.path)
+            })))
+    };
+    let server = server(signer()).await;
+
+    ApiClientBuilder::new(&server.uri())
+        .authentication(Authentication::new_hmac(signer()))
+        .build()
+        .unwrap()
+        .delete("lyric/1")
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests[0].headers.contains_key("x-signature"));
+    assert!(requests[0].headers.contains_key("x-date"));
+}
@@ -0,0 +1,128 @@
+use futures_util::TryStreamExt;
+use rest_json_client::{ApiClientBuilder, Error, StatusCode};
+use serde::Deserialize;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    message: String,
+}
+
+#[tokio::test]
+async fn server_error_is_status_error_with_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/message"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("<html>Oops</html>"))
+        .mount(&server)
+        .await;
+
+    let error = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .build()
+        .unwrap()
+        .get::<Message>("message")
+        .await
+        .unwrap_err();
+
+    match error {
+        Error::Status(error) => {
+            assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(error.body(), "<html>Oops</html>");
+        }
+        other => panic!("Unexpected error {other}"),
+    }
+}
+
+#[tokio::test]
+async fn not_found_with_json_body_is_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    let result = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .build()
+        .unwrap()
+        .get::<serde_json::Value>("message")
+        .await;
+
+    assert_eq!(
+        result.err().and_then(|e| e.status()),
+        Some(StatusCode::NOT_FOUND)
+    );
+}
+
+#[tokio::test]
+async fn error_for_status_disabled_deserializes_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(422).set_body_string(r#"{"message":"Title missing"}"#))
+        .mount(&server)
+        .await;
+
+    let message = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .error_for_status(false)
+        .build()
+        .unwrap()
+        .post::<_, Message>("message", ())
+        .await
+        .unwrap();
+
+    assert_eq!(message.message, "Title missing");
+}
+
+#[tokio::test]
+async fn problem_details_are_decoded() {
+    let server = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .respond_with(ResponseTemplate::new(409).set_body_raw(
+            r#"{"type":"https://example.com/probs/in-use","title":"Lyric in use","status":409,"detail":"Lyric is part of 2 playlists","instance":"/lyric/1","playlists":2}"#,
+            "application/problem+json",
+        ))
+        .mount(&server)
+        .await;
+
+    let error = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .build()
+        .unwrap()
+        .delete("lyric/1")
+        .await
+        .unwrap_err();
+
+    let Error::Status(error) = error else {
+        panic!("Unexpected error {error}");
+    };
+    let problem = error.problem().unwrap();
+    assert_eq!(problem.title.as_deref(), Some("Lyric in use"));
+    assert_eq!(problem.status, Some(409));
+    assert_eq!(problem.instance.as_deref(), Some("/lyric/1"));
+    assert_eq!(problem.extensions["playlists"], 2);
+    assert_eq!(
+        error.to_string(),
+        "Status: 409 Conflict, Lyric in use: Lyric is part of 2 playlists"
+    );
+}
+
+#[tokio::test]
+async fn error_for_status_disabled_streams_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(422).set_body_string(r#"[{"message":"Title missing"}]"#),
+        )
+        .mount(&server)
+        .await;
+
+    let messages = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .error_for_status(false)
+        .build()
+        .unwrap()
+        .get_stream::<Message>("message")
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(messages[0].message, "Title missing");
+}
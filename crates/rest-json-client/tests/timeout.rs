@@ -0,0 +1,27 @@
+use rest_json_client::{ApiClientBuilder, Error};
+use std::time::Duration;
+use wiremock::{Mock, MockServer, ResponseTemplate, matchers::any};
+
+#[tokio::test]
+async fn hung_server_results_in_timeout_error() {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("{}")
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let error = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap()
+        .get::<serde_json::Value>("slow")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Timeout(_)), "{error}");
+    assert!(error.is_timeout());
+}
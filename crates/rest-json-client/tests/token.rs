@@ -0,0 +1,143 @@
+mod common;
+
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use rest_json_client::{Authentication, Error, SignedTokenProvider};
+use serde_json::{Value, json};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn jwt(exp: u64) -> String {
+    let encode = |value: Value| BASE64_URL_SAFE_NO_PAD.encode(value.to_string());
+    format!(
+        "{}.{}.signature",
+        encode(json!({"alg": "HS256"})),
+        encode(json!({"exp": exp}))
+    )
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+async fn token_endpoint(server: &MockServer, token: &str) {
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(header("Signature", "signed"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(token))
+        .mount(server)
+        .await;
+}
+
+fn provider(server: &MockServer) -> SignedTokenProvider {
+    SignedTokenProvider::new(
+        &format!("{}/token", server.uri()),
+        "signed",
+        json!({"username": "paul"}),
+    )
+    .unwrap()
+}
+
+async fn token_requests(server: &MockServer) -> usize {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|request| request.url.path() == "/token")
+        .count()
+}
+
+#[tokio::test]
+async fn token_is_cached_until_it_expires() {
+    let server = MockServer::start().await;
+    let token = jwt(now() + 3600);
+    token_endpoint(&server, &token).await;
+    Mock::given(method("GET"))
+        .and(header("Authorization", format!("Bearer {token}")))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    let client = common::builder(&server.uri())
+        .authentication(Authentication::new_token_provider(provider(&server)))
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    client.get::<Value>("playlist").await.unwrap();
+
+    assert_eq!(token_requests(&server).await, 1);
+}
+
+#[tokio::test]
+async fn token_is_refreshed_before_it_expires() {
+    let server = MockServer::start().await;
+    token_endpoint(&server, &jwt(now() + 10)).await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    let client = common::builder(&server.uri())
+        .authentication(Authentication::new_token_provider(provider(&server)))
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+    client.get::<Value>("playlist").await.unwrap();
+
+    assert_eq!(token_requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn unauthorized_response_refreshes_token_once() {
+    let server = MockServer::start().await;
+    token_endpoint(&server, &jwt(now() + 3600)).await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    common::builder(&server.uri())
+        .authentication(Authentication::new_token_provider(provider(&server)))
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap();
+
+    assert_eq!(token_requests(&server).await, 2);
+}
+
+#[tokio::test]
+async fn slow_token_request_times_out() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(jwt(now() + 3600))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+    let provider = provider(&server).timeout(Duration::from_millis(100));
+
+    let error = common::builder(&server.uri())
+        .authentication(Authentication::new_token_provider(provider))
+        .build()
+        .unwrap()
+        .get::<Value>("lyric")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Timeout(_)), "{error:?}");
+}
@@ -0,0 +1,94 @@
+use rest_json_client::{
+    ApiClientBuilder, Error, RetryPolicy, TraceContext, TraceParent, header::HeaderName,
+};
+use serde_json::Value;
+use std::time::Duration;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const X_CORRELATION_ID: HeaderName = HeaderName::from_static("x-correlation-id");
+
+async fn received_header(server: &MockServer, name: &str) -> Vec<String> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|request| request.headers[name].to_str().unwrap().to_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn generated_trace_context_and_request_id_are_sent() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    let client = ApiClientBuilder::new(&server.uri())
+        .trace_context(TraceContext::Generated)
+        .request_id_header(X_CORRELATION_ID)
+        .retry_policy(RetryPolicy::new(2).initial_backoff(Duration::from_millis(1)))
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+
+    let traceparents = received_header(&server, "traceparent").await;
+    assert_eq!(traceparents.len(), 2);
+    let trace_parent = TraceParent::parse(&traceparents[0]).unwrap();
+    assert!(trace_parent.sampled);
+    assert_eq!(trace_parent.traceparent(), traceparents[0]);
+
+    let request_ids = received_header(&server, "x-correlation-id").await;
+    assert_eq!(request_ids[0].len(), 36);
+    assert_eq!(request_ids[0], request_ids[1]);
+}
+
+#[tokio::test]
+async fn current_trace_is_continued() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    let incoming = TraceParent::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        .unwrap()
+        .with_tracestate("lipl=1");
+    let current = incoming.clone();
+    let client = ApiClientBuilder::new(&server.uri())
+        .trace_context(TraceContext::current(move || Some(current.clone())))
+        .build()
+        .unwrap();
+    client.get::<Value>("lyric").await.unwrap();
+
+    let outgoing = TraceParent::parse(&received_header(&server, "traceparent").await[0]).unwrap();
+    assert_eq!(outgoing.trace_id, incoming.trace_id);
+    assert_ne!(outgoing.parent_id, incoming.parent_id);
+    assert_eq!(received_header(&server, "tracestate").await, ["lipl=1"]);
+}
+
+#[tokio::test]
+async fn echoed_request_id_is_part_of_status_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500).insert_header("X-Correlation-Id", "abc-123"))
+        .mount(&server)
+        .await;
+
+    let client = ApiClientBuilder::new(&server.uri())
+        .request_id_header(X_CORRELATION_ID)
+        .build()
+        .unwrap();
+    let Error::Status(error) = client.get::<Value>("lyric").await.unwrap_err() else {
+        panic!("expected a status error");
+    };
+    assert_eq!(error.request_id(), Some("abc-123"));
+    assert!(error.to_string().contains("abc-123"));
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "tracing")]
+
+use rest_json_client::{ApiClientBuilder, Authentication, Method, header::HeaderName};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tracing::{
+    Event, Id, Metadata, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Record},
+};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+type Fields = BTreeMap<String, String>;
+
+/// Keeps the fields of all spans and events
+#[derive(Clone, Default)]
+struct Recorder {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<BTreeMap<u64, (&'static str, Fields)>>>,
+    events: Arc<Mutex<Vec<Fields>>>,
+}
+
+struct Visitor<'a>(&'a mut Fields);
+
+impl Visit for Visitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name().to_owned(), format!("{value:?}"));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut fields = Fields::new();
+        attributes.record(&mut Visitor(&mut fields));
+        self.spans
+            .lock()
+            .unwrap()
+            .insert(id, (attributes.metadata().name(), fields));
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some((_, fields)) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            values.record(&mut Visitor(fields));
+        }
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut Visitor(&mut fields));
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+impl Recorder {
+    fn span(&self, name: &str) -> Fields {
+        self.spans
+            .lock()
+            .unwrap()
+            .values()
+            .find(|(span_name, _)| *span_name == name)
+            .map(|(_, fields)| fields.clone())
+            .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn request_span_records_route_status_and_attempt() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+    let client = ApiClientBuilder::new(&server.uri())
+        .authentication(Authentication::new_basic("paul", "s3cr3t"))
+        .sensitive_header(HeaderName::from_static("x-session"))
+        .build()
+        .unwrap();
+    client
+        .request(Method::GET, "lyric/42")
+        .route("lyric/{id}")
+        .header("X-Session", "session-id")
+        .send_json::<Value>()
+        .await
+        .unwrap();
+
+    let span = recorder.span("http.request");
+    assert_eq!(span["http.request.method"], "GET");
+    assert_eq!(span["http.route"], "lyric/{id}");
+    assert_eq!(span["url.path"], "/lyric/42");
+    assert_eq!(span["http.response.status_code"], "200");
+    assert_eq!(span["http.response.body.size"], "2");
+    assert_eq!(span["attempt"], "1");
+    assert!(span.contains_key("latency_ms"));
+
+    let events = recorder.events.lock().unwrap();
+    let sending = events
+        .iter()
+        .find(|event| event["message"] == "sending request")
+        .unwrap();
+    assert!(sending["headers"].contains("<redacted>"));
+    assert!(!sending["headers"].contains("session-id"));
+    // base64 of paul:s3cr3t
+    assert!(!sending["headers"].contains("cGF1bDpzM2NyM3Q="));
+}
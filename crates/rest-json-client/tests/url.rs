@@ -0,0 +1,101 @@
+use rest_json_client::{ApiClientBuilder, Authentication, Error, path_segments};
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/lyric/Mijn%20lied%2FA"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn prefix_without_trailing_slash_and_path_with_leading_slash() {
+    let server = server().await;
+    let client = ApiClientBuilder::new(&format!("{}/api/v1", server.uri()))
+        .build()
+        .unwrap();
+
+    client
+        .get::<Value>(&format!(
+            "/{}",
+            path_segments(["lyric", "Mijn lied/A"]).unwrap()
+        ))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn prefix_with_trailing_slash() {
+    let server = server().await;
+    let client = ApiClientBuilder::new(&format!("{}/api/v1/", server.uri()))
+        .build()
+        .unwrap();
+
+    client
+        .get::<Value>(&path_segments(["lyric", "Mijn lied/A"]).unwrap())
+        .await
+        .unwrap();
+}
+
+#[test]
+fn invalid_prefix_fails_at_build() {
+    let result = ApiClientBuilder::new("no-scheme/api").build();
+    assert!(matches!(result, Err(Error::Url(_))));
+}
+
+#[tokio::test]
+async fn dot_segments_are_refused() {
+    let server = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+    let client = ApiClientBuilder::new(&format!("{}/api/", server.uri()))
+        .build()
+        .unwrap();
+
+    for value in [".", ".."] {
+        let result = path_segments(["playlist", "p1", "lyric", value]);
+        assert!(matches!(result, Err(Error::Url(_))), "{value}");
+    }
+    let uri = path_segments(["playlist", "p1", "lyric", "%2E%2E"]).unwrap();
+    client.delete(&uri).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests[0].url.path(), "/api/playlist/p1/lyric/%252E%252E");
+}
+
+#[tokio::test]
+async fn absolute_url_is_refused() {
+    let other = MockServer::start().await;
+    let client = ApiClientBuilder::new("http://127.0.0.1:1/api/")
+        .authentication(Authentication::new_bearer("tok"))
+        .build()
+        .unwrap();
+
+    for uri in [
+        format!("{}/steal", other.uri()),
+        "//127.0.0.1:1/steal".to_owned(),
+    ] {
+        let result = client.get::<Value>(&uri).await;
+        assert!(matches!(result, Err(Error::Url(_))), "{uri}");
+    }
+    assert!(other.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn path_outside_of_prefix_is_refused() {
+    let client = ApiClientBuilder::new("http://127.0.0.1:1/api/v1/")
+        .build()
+        .unwrap();
+
+    for uri in ["../admin", "lyric/../../admin", "%2E%2E/admin"] {
+        let result = client.get::<Value>(uri).await;
+        assert!(matches!(result, Err(Error::Url(_))), "{uri}");
+    }
+}
@@ -0,0 +1,85 @@
+use rest_json_client::{ApiClientBuilder, Method, StatusCode};
+use serde_json::{Value, json};
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn merge_patch_sends_merge_patch_content_type() {
+    let server = MockServer::start().await;
+    Mock::given(method("PATCH"))
+        .and(path("/lyric/1"))
+        .and(header("content-type", "application/merge-patch+json"))
+        .and(body_json(json!({"title": "Hallo"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": 1, "title": "Hallo"})))
+        .mount(&server)
+        .await;
+
+    let lyric = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .build()
+        .unwrap()
+        .merge_patch::<_, Value>("lyric/1", json!({"title": "Hallo"}))
+        .await
+        .unwrap();
+
+    assert_eq!(lyric["title"], "Hallo");
+}
+
+#[tokio::test]
+async fn json_patch_sends_json_patch_content_type() {
+    let server = MockServer::start().await;
+    Mock::given(method("PATCH"))
+        .and(header("content-type", "application/json-patch+json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .mount(&server)
+        .await;
+
+    ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .build()
+        .unwrap()
+        .json_patch::<_, Value>(
+            "lyric/1",
+            json!([{"op": "replace", "path": "/title", "value": "Hallo"}]),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn head_returns_status_without_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/lyric/1"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let head = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .build()
+        .unwrap()
+        .head("lyric/1")
+        .await
+        .unwrap();
+
+    assert_eq!(head.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn options_lists_allowed_methods() {
+    let server = MockServer::start().await;
+    Mock::given(method("OPTIONS"))
+        .respond_with(ResponseTemplate::new(204).insert_header("Allow", "GET, PATCH, DELETE"))
+        .mount(&server)
+        .await;
+
+    let head = ApiClientBuilder::new(&format!("{}/", server.uri()))
+        .build()
+        .unwrap()
+        .options("lyric/1")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        head.allow(),
+        vec![Method::GET, Method::PATCH, Method::DELETE]
+    );
+}